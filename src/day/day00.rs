@@ -1,10 +1,10 @@
 use crate::solution::SolutionTuple;
 use crate::util::measure::MeasureContext;
-use winnow::{PResult, Parser};
+use winnow::{ModalResult, Parser};
 
 type PreparedInput = Vec<i64>;
 
-fn line(input: &mut &str) -> PResult<i64> {
+fn line(input: &mut &str) -> ModalResult<i64> {
     winnow::ascii::dec_int.parse_next(input)
 }
 
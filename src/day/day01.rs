@@ -1,6 +1,6 @@
 use crate::solution::SolutionTuple;
+use crate::util::hash::FxHashMap;
 use crate::util::measure::MeasureContext;
-use rustc_hash::FxHashMap;
 
 type PreparedInput = (Vec<u32>, Vec<u32>);
 
@@ -1,5 +1,5 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::Grid;
+use crate::util::grid::{Grid, WindowEdge};
 use crate::util::measure::MeasureContext;
 
 type PreparedInput = Grid<u8>;
@@ -10,53 +10,50 @@ fn prepare(input: &str) -> PreparedInput {
 
 #[inline]
 fn test_slice(slice: &[u8]) -> bool {
-    slice == [b'X', b'M', b'A', b'S'] || slice == [b'S', b'A', b'M', b'X']
+    slice == b"XMAS" || slice == b"SAMX"
 }
 
 fn solve_part1(input: &PreparedInput) -> usize {
-    let mut count = input.rows().fold(0, |count, row| {
-        row.windows(4).fold(count, |mut count, slice| {
-            count += if test_slice(slice) { 1 } else { 0 };
-            count
-        })
-    });
+    let count = input
+        .rows()
+        .map(|row| row.windows(4).filter(|slice| test_slice(slice)).count())
+        .sum::<usize>();
 
     fn handle_slice_iterator(
         buf: &mut Vec<u8>,
         slices: impl Iterator<Item = impl Iterator<Item = u8>>,
     ) -> usize {
-        slices.fold(0, |count, slice| {
-            buf.clear();
-            buf.extend(slice);
-            buf.windows(4).fold(count, |mut count, slice| {
-                count += if test_slice(slice) { 1 } else { 0 };
-                count
+        slices
+            .map(|slice| {
+                buf.clear();
+                buf.extend(slice);
+                buf.windows(4).filter(|slice| test_slice(slice)).count()
             })
-        })
+            .sum()
     }
 
-    let mut buf = Vec::with_capacity(input.dimensions.0);
-    count += handle_slice_iterator(&mut buf, input.columns());
-    count += handle_slice_iterator(&mut buf, input.diagonals_lower());
-    count += handle_slice_iterator(&mut buf, input.diagonals_upper().skip(1));
-    count += handle_slice_iterator(&mut buf, input.anti_diagonals_upper());
-    count += handle_slice_iterator(&mut buf, input.anti_diagonals_lower().skip(1));
+    let mut buf = Vec::with_capacity(input.dimensions.0.max(input.dimensions.1));
     count
+        + handle_slice_iterator(&mut buf, input.columns())
+        + handle_slice_iterator(
+            &mut buf,
+            input.diagonals().map(|line| line.map(|(_, c)| *c)),
+        )
+        + handle_slice_iterator(
+            &mut buf,
+            input.anti_diagonals().map(|line| line.map(|(_, c)| *c)),
+        )
 }
 
 fn solve_part2(input: &PreparedInput) -> usize {
+    let is_mas = |a: u8, b: u8| a == b'M' && b == b'S' || a == b'S' && b == b'M';
     input
-        .iter_windows3_where(|c| *c == b'A')
-        .fold(0, |mut count, window| {
-            if (*window.top_left() == b'M' && *window.bottom_right() == b'S'
-                || *window.top_left() == b'S' && *window.bottom_right() == b'M')
-                && (*window.top_right() == b'M' && *window.bottom_left() == b'S'
-                    || *window.top_right() == b'S' && *window.bottom_left() == b'M')
-            {
-                count += 1
-            }
-            count
+        .iter_windows_where::<3, 3>(WindowEdge::Skip, |c| *c == b'A')
+        .filter(|window| {
+            is_mas(*window.get(0, 0), *window.get(2, 2))
+                && is_mas(*window.get(0, 2), *window.get(2, 0))
         })
+        .count()
 }
 
 pub fn solve(ctx: &mut MeasureContext, input: &str) -> SolutionTuple {
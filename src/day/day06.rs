@@ -1,117 +1,97 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::Grid;
+use crate::util::grid::{FromCell, Grid};
+use crate::util::hash::FxHashSet;
 use crate::util::intset::IntSet;
 use crate::util::measure::MeasureContext;
 use crate::util::position::{Direction, Position, RotationalDirection};
-use rustc_hash::FxHashSet;
 
-#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy)]
+#[derive(Debug, PartialEq, Eq, Hash, Clone, Copy, Default)]
 enum Tile {
+    #[default]
     Empty,
     Obstruction,
-    GuardUpwardFacing,
+}
+impl FromCell for Tile {
+    fn from_cell(cell: u8) -> Option<Self> {
+        match cell {
+            b'.' => Some(Tile::Empty),
+            b'#' => Some(Tile::Obstruction),
+            _ => None,
+        }
+    }
 }
 
-type PreparedInput = Grid<Tile>;
+/// Grid and the position of the upward facing guard
+type PreparedInput = (Grid<Tile>, Position);
 
 fn prepare(input: &str) -> PreparedInput {
-    Grid::from_rows(input.lines().map(|l| {
-        l.chars().map(|c| match c {
-            '.' => Tile::Empty,
-            '#' => Tile::Obstruction,
-            '^' => Tile::GuardUpwardFacing,
-            _ => panic!("Unexpected char {}", c),
-        })
-    }))
-}
-
-#[derive(Debug, Eq, PartialEq, Copy, Clone)]
-enum WalkResult {
-    OutOfBounds,
-    Loop,
+    let (grid, markers) = Grid::parse(input, b"^").unwrap_or_else(|e| panic!("{}", e));
+    (grid, markers.get(b'^').unwrap())
 }
 
 type VisitedWithPositionSet = IntSet;
 
-fn walk(
-    grid: &PreparedInput,
+/// Whether the guard ends up in a loop with an extra obstruction, jumping from obstacle to
+/// obstacle.
+fn is_loop(
+    grid: &Grid<Tile>,
     mut visited: VisitedWithPositionSet,
     pos: &Position,
     direction: &Direction,
-    obstruction_test_fn: impl Fn(&Position) -> bool,
-    mut each_fn: impl FnMut(&Position, &Direction, &VisitedWithPositionSet),
-) -> WalkResult {
-    assert!(grid.dimensions.0 < 256 && grid.dimensions.1 < 256);
-
+    obstruction: &Position,
+) -> bool {
     let mut pos = *pos;
     let mut direction = *direction;
-
-    'next_direction: loop {
-        each_fn(&pos, &direction, &visited);
-        for next_pos in pos.positions(&grid.dimensions, &direction) {
-            let next = unsafe { grid.get_unchecked(&next_pos) };
-
-            if matches!(next, Tile::Obstruction) || obstruction_test_fn(&next_pos) {
-                let pos_and_direction: u32 =
-                    ((pos.0 as u32) << 10) | ((pos.1 as u32) << 2) | direction as u32;
-                let previous_visit = visited.insert(pos_and_direction as usize);
-                if !previous_visit {
-                    return WalkResult::Loop;
-                }
-
-                direction = direction.rotated(&RotationalDirection::Clockwise);
-                continue 'next_direction;
-            }
-            each_fn(&next_pos, &direction, &visited);
-            pos = next_pos;
+    loop {
+        let cast = grid.cast_ray(&pos, &(&direction).into(), |next_pos, tile| {
+            matches!(tile, Tile::Obstruction) || next_pos == obstruction
+        });
+        if cast.hit.is_none() {
+            return false;
         }
-        return WalkResult::OutOfBounds;
+        pos = cast.last;
+        if !visited.insert(pos.pack_with_direction(&grid.dimensions, &direction)) {
+            return true;
+        }
+        direction = direction.rotated(&RotationalDirection::Clockwise);
     }
 }
 
-fn solve_both(input: &PreparedInput) -> (usize, usize) {
+fn solve_both((input, pos): &PreparedInput) -> (usize, usize) {
     let mut visited = FxHashSet::default();
-
-    let pos = input
-        .positions_where(|tile| *tile == Tile::GuardUpwardFacing)
-        .next()
-        .unwrap();
-
+    let mut visited_with_direction = IntSet::with_maximum(input.dimensions.size() * 4);
     let mut extra_obstructions = 0;
 
-    let result = walk(
-        input,
-        IntSet::with_maximum(input.dimensions.0 << 10),
-        &pos,
-        &Direction::Up,
-        |_| false,
-        |pos, direction, visited_with_direction| {
-            visited.insert(*pos);
-
-            let Some(obstruction_pos) = pos.checked_moved(&input.dimensions, direction) else {
-                return;
-            };
-            let obstruction_tile = unsafe { input.get_unchecked(&obstruction_pos) };
+    let mut pos = *pos;
+    let mut direction = Direction::Up;
+    loop {
+        visited.insert(pos);
+        let Some(next_pos) = pos.checked_moved(&input.dimensions, &direction) else {
+            break;
+        };
+
+        if matches!(input.get(&next_pos), Tile::Obstruction) {
+            assert!(
+                visited_with_direction
+                    .insert(pos.pack_with_direction(&input.dimensions, &direction))
+            );
+            direction = direction.rotated(&RotationalDirection::Clockwise);
+            continue;
+        }
 
-            if matches!(obstruction_tile, Tile::Empty) && !visited.contains(&obstruction_pos) {
-                let rotated_direction = direction.rotated(&RotationalDirection::Clockwise);
-                if matches!(
-                    walk(
-                        input,
-                        visited_with_direction.clone(),
-                        pos,
-                        &rotated_direction,
-                        |pos| obstruction_pos == *pos,
-                        |_, _, _| {}
-                    ),
-                    WalkResult::Loop
-                ) {
-                    extra_obstructions += 1;
-                }
-            }
-        },
-    );
-    assert_eq!(result, WalkResult::OutOfBounds);
+        if !visited.contains(&next_pos)
+            && is_loop(
+                input,
+                visited_with_direction.clone(),
+                &pos,
+                &direction.rotated(&RotationalDirection::Clockwise),
+                &next_pos,
+            )
+        {
+            extra_obstructions += 1;
+        }
+        pos = next_pos;
+    }
 
     (visited.len(), extra_obstructions)
 }
@@ -138,7 +118,9 @@ mod tests {
 ......#...";
     #[test]
     fn example_prepare() {
-        assert_eq!(prepare(EXAMPLE_INPUT).dimensions, (10, 10).into());
+        let (grid, pos) = prepare(EXAMPLE_INPUT);
+        assert_eq!(grid.dimensions, (10, 10).into());
+        assert_eq!(pos, Position(6, 4));
     }
     #[test]
     fn example_part1() {
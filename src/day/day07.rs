@@ -26,7 +26,7 @@ fn test<const CONCAT_ENABLED: bool>(numbers: &[u16], i: usize, expected_result:
     if i == 0 {
         return last_num == expected_result;
     }
-    if expected_result % last_num == 0
+    if expected_result.is_multiple_of(last_num)
         && test::<CONCAT_ENABLED>(numbers, i - 1, expected_result / last_num)
     {
         return true;
@@ -1,10 +1,10 @@
 use crate::solution::SolutionTuple;
 use crate::util::grid::Grid;
+use crate::util::hash::{FxHashMap, FxHashSet};
 use crate::util::measure::MeasureContext;
 use crate::util::position::{Dimensions, Position};
 use itertools::Itertools;
 use num::integer::gcd;
-use rustc_hash::{FxHashMap, FxHashSet};
 
 type PreparedInput = (Dimensions, FxHashMap<u8, Vec<Position>>);
 
@@ -1,50 +1,38 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::Grid;
+use crate::util::grid::BackedGrid;
+use crate::util::hash::{FxHashMap, FxHashSet};
 use crate::util::measure::MeasureContext;
-use crate::util::position::Direction;
-use rustc_hash::{FxHashMap, FxHashSet};
 
-type PreparedInput = Grid<u8>;
+/// Heights as ASCII digits, borrowed from the input
+type PreparedInput<'a> = BackedGrid<'a, u8>;
 
-fn prepare(input: &str) -> PreparedInput {
-    Grid::from_rows(
-        input
-            .lines()
-            .map(|line| line.as_bytes().iter().map(|b| *b - b'0')),
-    )
+fn prepare(input: &str) -> PreparedInput<'_> {
+    BackedGrid::from_data_and_row_separator(input.as_bytes(), b'\n')
 }
 
 fn solve_both(input: &PreparedInput) -> (usize, usize) {
     let mut set = input
-        .positions_where(|&num| num == 9)
+        .positions_where(|&num| num == b'9')
         .map(|pos| (pos, (FxHashSet::from_iter([pos]), 1)))
         .collect::<FxHashMap<_, _>>();
 
-    for i in (0..9).rev() {
+    for i in (b'0'..=b'8').rev() {
         set = input
             .positions_where(|&num| num == i)
             .map(|pos| {
                 (
                     pos,
-                    [
-                        Direction::Up,
-                        Direction::Down,
-                        Direction::Left,
-                        Direction::Right,
-                    ]
-                    .iter()
-                    .filter_map(|direction| {
-                        pos.checked_moved(&input.dimensions, direction)
-                            .and_then(|above| set.get(&above))
-                    })
-                    .fold(
-                        (FxHashSet::default(), 0),
-                        |(mut set, mut count), (set_item, count_item)| {
-                            set.extend(set_item);
-                            count += count_item;
-                            (set, count)
-                        },
-                    ),
+                    input
+                        .neighbors4(&pos)
+                        .filter_map(|(_, above, _)| set.get(&above))
+                        .fold(
+                            (FxHashSet::default(), 0),
+                            |(mut set, mut count), (set_item, count_item)| {
+                                set.extend(set_item);
+                                count += count_item;
+                                (set, count)
+                            },
+                        ),
                 )
             })
             .collect::<FxHashMap<_, _>>();
@@ -1,6 +1,6 @@
 use crate::solution::SolutionTuple;
+use crate::util::hash::FxHashMap;
 use crate::util::measure::MeasureContext;
-use rustc_hash::{FxBuildHasher, FxHashMap};
 
 type PreparedInput = Vec<u64>;
 
@@ -18,7 +18,7 @@ fn blink_iterations(input: &PreparedInput, n: u32) -> u64 {
         .collect::<FxHashMap<_, _>>();
 
     for _ in 0..n {
-        let mut next = FxHashMap::with_capacity_and_hasher(stones.len() * 2, FxBuildHasher);
+        let mut next = FxHashMap::with_capacity_and_hasher(stones.len() * 2, Default::default());
         stones.into_iter().for_each(|(stone, count)| {
             if stone == 0 {
                 *next.entry(1).or_insert(0) += count;
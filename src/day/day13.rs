@@ -1,41 +1,30 @@
 use crate::solution::SolutionTuple;
 use crate::util::measure::MeasureContext;
+use crate::util::position::Point;
 use num::integer::div_rem;
-use winnow::ascii::dec_uint;
+use winnow::ascii::dec_int;
 use winnow::combinator::{preceded, separated_pair};
 use winnow::{ModalResult, Parser};
 
-#[derive(Debug, Copy, Clone)]
-struct ButtonBehaviour {
-    x: u8,
-    y: u8,
-}
-
-#[derive(Debug, Copy, Clone)]
-struct Prize {
-    x: u64,
-    y: u64,
-}
-
 #[derive(Debug, Copy, Clone)]
 struct Machine {
-    a: ButtonBehaviour,
-    b: ButtonBehaviour,
-    prize: Prize,
+    a: Point<i64>,
+    b: Point<i64>,
+    prize: Point<i64>,
 }
 
 type PreparedInput = Vec<Machine>;
 
 fn prepare(input: &str) -> PreparedInput {
-    fn button_behaviour(input: &mut &str) -> ModalResult<ButtonBehaviour> {
-        separated_pair(preceded("X+", dec_uint), ", ", preceded("Y+", dec_uint))
-            .map(|(x, y)| ButtonBehaviour { x, y })
+    fn button_behaviour(input: &mut &str) -> ModalResult<Point<i64>> {
+        separated_pair(preceded("X+", dec_int), ", ", preceded("Y+", dec_int))
+            .map(|(x, y)| Point(y, x))
             .parse_next(input)
     }
 
-    fn prize(input: &mut &str) -> ModalResult<Prize> {
-        separated_pair(preceded("X=", dec_uint), ", ", preceded("Y=", dec_uint))
-            .map(|(x, y)| Prize { x, y })
+    fn prize(input: &mut &str) -> ModalResult<Point<i64>> {
+        separated_pair(preceded("X=", dec_int), ", ", preceded("Y=", dec_int))
+            .map(|(x, y)| Point(y, x))
             .parse_next(input)
     }
 
@@ -56,18 +45,11 @@ fn prepare(input: &str) -> PreparedInput {
 }
 
 fn cost(machine: &Machine) -> Option<i64> {
-    let (b_presses, remainder) = div_rem(
-        machine.prize.y as i64 * machine.a.x as i64 - machine.prize.x as i64 * machine.a.y as i64,
-        machine.b.y as i64 * machine.a.x as i64 - machine.b.x as i64 * machine.a.y as i64,
-    );
-    if b_presses < 0 || remainder != 0 {
-        return None;
-    }
-    let (a_presses, remainder) = div_rem(
-        machine.prize.x as i64 - b_presses * machine.b.x as i64,
-        machine.a.x as i64,
-    );
-    if a_presses < 0 || remainder != 0 {
+    // Cramer's rule for a * presses_a + b * presses_b = prize
+    let determinant = machine.a.cross(&machine.b);
+    let (a_presses, a_remainder) = div_rem(machine.prize.cross(&machine.b), determinant);
+    let (b_presses, b_remainder) = div_rem(machine.a.cross(&machine.prize), determinant);
+    if a_presses < 0 || b_presses < 0 || a_remainder != 0 || b_remainder != 0 {
         return None;
     }
     Some(a_presses * 3 + b_presses)
@@ -81,10 +63,7 @@ fn solve_part2(input: &PreparedInput) -> i64 {
     input
         .iter()
         .map(|machine| Machine {
-            prize: Prize {
-                x: machine.prize.x + 10000000000000,
-                y: machine.prize.y + 10000000000000,
-            },
+            prize: machine.prize + Point(10000000000000, 10000000000000),
             ..*machine
         })
         .filter_map(|machine| cost(&machine))
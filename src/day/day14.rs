@@ -1,7 +1,8 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::Grid;
+use crate::util::grid::{BitGrid, FrameWriter, Torus};
 use crate::util::measure::MeasureContext;
 use crate::util::position::{Dimensions, Position, PositionOffset};
+use std::ops::Range;
 use winnow::ascii::{dec_int, dec_uint};
 use winnow::combinator::{preceded, separated_pair};
 use winnow::{ModalResult, Parser};
@@ -52,26 +53,52 @@ fn solve_part1(input: &PreparedInput, dimensions: &Dimensions) -> usize {
 }
 
 fn solve_part2(input: &PreparedInput, dimensions: &Dimensions) -> usize {
-    let mut grid = Grid::from_dimensions(*dimensions, false);
+    let mut robots = Torus::new(BitGrid::new(*dimensions));
+    let velocities = input
+        .iter()
+        .map(|robot| robots.reduce_velocity(&robot.vel))
+        .collect::<Vec<_>>();
     'next_second: for i in 0usize.. {
-        let positions = input.iter().map(|robot| {
-            robot
-                .pos
-                .wrapping_offset(dimensions, &(robot.vel * i as isize))
-        });
-        for pos in positions {
-            if !grid.insert(&pos) {
-                grid.clear();
+        let steps = robots.reduce_steps(i);
+        for (robot, velocity) in input.iter().zip(&velocities) {
+            let pos = robots.offset_steps_reduced(&robot.pos, velocity, &steps);
+            if !robots.grid.insert(&pos) {
+                robots.grid.clear();
                 continue 'next_second;
             }
         }
 
-        // println!("{}", grid);
+        // println!("{}", robots.grid);
         return i;
     }
     panic!();
 }
 
+/// Writes one frame with the robots for every second in `seconds`. Starting from 0, the tree
+/// shows up in the frame numbered by the part 2 answer.
+pub fn write_frames(
+    input: &str,
+    seconds: Range<usize>,
+    frames: &mut FrameWriter,
+) -> std::io::Result<()> {
+    let dimensions = Dimensions(103, 101);
+    let input = prepare(input);
+    let mut robots = BitGrid::new(dimensions);
+    for second in seconds {
+        robots.clear();
+        robots.extend(input.iter().map(|robot| {
+            robot
+                .pos
+                .wrapping_offset(&dimensions, &(robot.vel * second as isize))
+        }));
+        let image = robots
+            .to_grid()
+            .to_image(|robot| if *robot { [0, 200, 0] } else { [0, 0, 0] });
+        frames.write(&image)?;
+    }
+    Ok(())
+}
+
 pub fn solve(ctx: &mut MeasureContext, input: &str) -> SolutionTuple {
     let dimensions = Dimensions(103, 101);
     let input = ctx.measure("prepare", || prepare(input));
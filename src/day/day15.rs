@@ -1,15 +1,35 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::{CellDisplay, Grid};
+use crate::util::grid::{CellDisplay, FromCell, Grid, ToCell};
 use crate::util::measure::MeasureContext;
 use crate::util::position::{Direction, Position};
 use std::fmt::{Formatter, Write};
 
-#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Default)]
 enum Tile {
+    #[default]
     Empty,
     Wall,
     Box,
 }
+impl FromCell for Tile {
+    fn from_cell(cell: u8) -> Option<Self> {
+        match cell {
+            b'.' => Some(Tile::Empty),
+            b'#' => Some(Tile::Wall),
+            b'O' => Some(Tile::Box),
+            _ => None,
+        }
+    }
+}
+impl ToCell for Tile {
+    fn to_cell(&self) -> u8 {
+        match self {
+            Tile::Empty => b'.',
+            Tile::Wall => b'#',
+            Tile::Box => b'O',
+        }
+    }
+}
 impl CellDisplay for Tile {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         f.write_char(match self {
@@ -28,21 +48,8 @@ fn prepare(input: &str) -> PreparedInput {
     let movements_section = sections.next().unwrap();
     assert_eq!(sections.next(), None);
 
-    let grid_items = grid_section.lines().map(|line| line.as_bytes());
-
-    let grid = Grid::from_rows(grid_items.clone().map(|row| {
-        row.iter().map(|c| match *c {
-            b'.' | b'@' => Tile::Empty,
-            b'#' => Tile::Wall,
-            b'O' => Tile::Box,
-            _ => panic!(),
-        })
-    }));
-
-    let starting_position = grid_items
-        .enumerate()
-        .find_map(|(y, row)| row.iter().position(|&c| c == b'@').map(|x| Position(y, x)))
-        .unwrap();
+    let (grid, markers) = Grid::parse(grid_section, b"@").unwrap_or_else(|e| panic!("{}", e));
+    let starting_position = markers.get(b'@').unwrap();
 
     let movements = movements_section
         .as_bytes()
@@ -64,7 +71,8 @@ fn gps(pos: &Position) -> usize {
     pos.0 * 100 + pos.1
 }
 
-fn solve_part1(input: &PreparedInput) -> usize {
+/// Warehouse and robot position after all movements
+fn simulate_part1(input: &PreparedInput) -> (Grid<Tile>, Position) {
     let (grid, starting_position, movements) = input;
     let mut grid = grid.clone();
     let mut position = *starting_position;
@@ -86,6 +94,11 @@ fn solve_part1(input: &PreparedInput) -> usize {
         }
     }
 
+    (grid, position)
+}
+
+fn solve_part1(input: &PreparedInput) -> usize {
+    let (grid, _) = simulate_part1(input);
     grid.positions_where(|tile| *tile == Tile::Box)
         .map(|pos| gps(&pos))
         .sum()
@@ -168,19 +181,17 @@ fn try_move(
 fn solve_part2(input: &PreparedInput) -> usize {
     let (grid, starting_position, movements) = input;
 
-    let mut grid = Grid::from_rows(grid.rows().map(|row| {
-        row.iter().flat_map(|tile| match tile {
-            Tile::Empty => [ScaledTile::Empty, ScaledTile::Empty],
-            Tile::Wall => [ScaledTile::Wall, ScaledTile::Wall],
-            Tile::Box => [ScaledTile::Box, ScaledTile::RightSideBox],
-        })
-    }));
+    let mut grid = grid.widened(|tile| match tile {
+        Tile::Empty => [ScaledTile::Empty, ScaledTile::Empty],
+        Tile::Wall => [ScaledTile::Wall, ScaledTile::Wall],
+        Tile::Box => [ScaledTile::Box, ScaledTile::RightSideBox],
+    });
 
     let mut moved_boxes = vec![];
 
     let mut position = Position(starting_position.0, starting_position.1 * 2);
     for mov in movements {
-        moved_boxes.truncate(0);
+        moved_boxes.clear();
         if !try_move(&grid, &position, mov, &mut moved_boxes) {
             continue;
         };
@@ -272,6 +283,21 @@ v^^>>><<^^<>>^v^<v^vv<>v^<<>^<^v^v><^<<<><<^<v><v<>vv>>v><v^<vv<>v^<<^";
         assert_eq!(solve_part1(&prepare(SMALLER_EXAMPLE)), 2028);
     }
     #[test]
+    fn part1_smaller_example_warehouse() {
+        let (grid, position) = simulate_part1(&prepare(SMALLER_EXAMPLE));
+        assert_eq!(
+            grid.to_text(&[(b'@', position)].into_iter().collect()),
+            "########
+#....OO#
+##.....#
+#.....O#
+#.#O@..#
+#...O..#
+#...O..#
+########"
+        );
+    }
+    #[test]
     fn part1_example() {
         assert_eq!(solve_part1(&prepare(EXAMPLE)), 10092);
     }
@@ -1,10 +1,8 @@
 use crate::solution::SolutionTuple;
-use crate::util::grid::Grid;
+use crate::util::grid::{BitGrid, UNREACHABLE, distances4_to};
 use crate::util::measure::MeasureContext;
-use crate::util::position::{DIRECTIONS, Dimensions, Position};
-use crate::util::solver::solve_breadth_first;
+use crate::util::position::{Dimensions, Position};
 use std::cmp::Ordering;
-use std::ops::ControlFlow;
 use winnow::ascii::dec_uint;
 use winnow::combinator::separated_pair;
 use winnow::{ModalResult, Parser};
@@ -24,47 +22,28 @@ fn prepare(input: &str) -> PreparedInput {
         .collect()
 }
 
-fn attempt(mut grid: Grid<bool>) -> Option<usize> {
+fn attempt(grid: &BitGrid) -> Option<usize> {
     let end_position = Position(grid.dimensions.0 - 1, grid.dimensions.1 - 1);
-
-    solve_breadth_first(
-        |stack, position, _| {
-            if *position == end_position {
-                return ControlFlow::Break(());
-            }
-
-            for direction in &DIRECTIONS {
-                let Some(next_position) = position.checked_moved(&grid.dimensions, direction)
-                else {
-                    continue;
-                };
-                let available = grid.insert(&next_position);
-                if !available {
-                    continue;
-                }
-                stack.push(next_position);
-            }
-
-            ControlFlow::Continue(())
-        },
-        vec![Position(0, 0)],
-    )
-    .map(|(_, steps)| steps)
+    let distances = distances4_to(grid.dimensions, [Position(0, 0)], &end_position, |pos| {
+        !grid.contains(pos)
+    });
+    let distance = *distances.get(&end_position);
+    (distance != UNREACHABLE).then_some(distance as usize)
 }
 
 fn solve_part1(input: &PreparedInput, dimensions: Dimensions, limit: usize) -> usize {
-    let grid = Grid::<bool>::from_positions(dimensions, input.iter().take(limit).cloned());
-    attempt(grid).unwrap()
+    let grid = BitGrid::from_positions(dimensions, input.iter().take(limit).cloned());
+    attempt(&grid).unwrap()
 }
 
 fn solve_part2(input: &PreparedInput, dimensions: Dimensions, skip: usize) -> String {
-    let grid = Grid::<bool>::from_positions(dimensions, input[0..skip].iter().cloned());
+    let grid = BitGrid::from_positions(dimensions, input[0..skip].iter().cloned());
 
     let indices = (skip..input.len()).collect::<Vec<_>>();
     let found = indices.binary_search_by(|i| {
         let mut grid = grid.clone();
         grid.extend(input[skip..*i].iter().copied());
-        match attempt(grid) {
+        match attempt(&grid) {
             None => Ordering::Greater,
             Some(_) => Ordering::Less,
         }
@@ -1,9 +1,9 @@
 #![allow(clippy::needless_range_loop)]
 
 use crate::solution::SolutionTuple;
+use crate::util::hash::FxHashSet;
 use crate::util::measure::MeasureContext;
 use itertools::Itertools;
-use rustc_hash::FxHashSet;
 
 #[derive(Clone, Eq, PartialEq, Hash)]
 struct Towel(Vec<u8>);
@@ -70,7 +70,7 @@ impl<'a> FromIterator<PartialTowel<'a>> for TowelSet<'a> {
 
 type PreparedInput<'a> = (TowelSet<'a>, Vec<Towel>);
 
-fn prepare(input: &str) -> PreparedInput {
+fn prepare(input: &str) -> PreparedInput<'_> {
     let mut sections = input.split("\n\n");
     let available_section = sections.next().unwrap();
     let target_section = sections.next().unwrap();
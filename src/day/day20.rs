@@ -3,36 +3,11 @@
 use crate::solution::SolutionTuple;
 use crate::util::grid::Grid;
 use crate::util::measure::MeasureContext;
-use crate::util::position::{DIRECTIONS, Position};
-use crate::util::solver::solve_breadth_first;
-use std::ops::ControlFlow::{Break, Continue};
+use crate::util::position::Position;
 
 fn parse(input: &str) -> (Grid<bool>, Position, Position) {
-    let mut start = None;
-    let mut end = None;
-    let grid = Grid::from_rows(input.lines().enumerate().map(|(y, line)| {
-        let start = &mut start;
-        let end = &mut end;
-        line.as_bytes()
-            .iter()
-            .enumerate()
-            .map(move |(x, c)| match c {
-                b'.' => false,
-                b'#' => true,
-                b'S' => {
-                    *start = Some(Position::from_yx(y, x));
-                    false
-                }
-                b'E' => {
-                    *end = Some(Position::from_yx(y, x));
-                    false
-                }
-                _ => panic!("Unexpected character"),
-            })
-            .collect::<Vec<_>>()
-    }));
-
-    (grid, start.unwrap(), end.unwrap())
+    let (grid, markers) = Grid::parse(input, b"SE").unwrap_or_else(|e| panic!("{}", e));
+    (grid, markers.get(b'S').unwrap(), markers.get(b'E').unwrap())
 }
 
 type PreparedInput = Grid<usize>;
@@ -40,53 +15,11 @@ type PreparedInput = Grid<usize>;
 /// Returns distance map from start
 /// Increased by one (so start=1), to remove branching in an inner loop later.
 fn prepare(grid: Grid<bool>, start: Position, end: Position) -> PreparedInput {
-    let mut distances = Grid::from_dimensions(grid.dimensions, 0);
-    distances.set(&start, 1);
-    solve_breadth_first(
-        |stack, pos, time| {
-            for direction in &DIRECTIONS {
-                let next_position = pos.moved(direction);
-                if grid.contains(&next_position) {
-                    continue;
-                }
-                let next_distance = distances.get_mut(&next_position);
-                if *next_distance != 0 && *next_distance <= time + 2 {
-                    continue;
-                }
-                *next_distance = time + 2;
-                if next_position == end {
-                    return Break(());
-                }
-                stack.push(next_position);
-            }
-            Continue(())
-        },
-        vec![start],
-    );
-
-    let best_time = *distances.get(&end);
-
-    solve_breadth_first(
-        |stack, pos, time| {
-            for direction in &DIRECTIONS {
-                let next_position = pos.moved(direction);
-                if grid.contains(&next_position) {
-                    continue;
-                }
-                let current_distance = distances.get_mut(&next_position);
-                if *current_distance <= best_time - (time + 1) {
-                    continue;
-                }
-                *current_distance = best_time - (time + 1);
-                if next_position == end {
-                    return Break(());
-                }
-                stack.push(next_position);
-            }
-            Continue(())
-        },
-        vec![end],
-    );
+    // Unreachable positions wrap around to 0
+    let distances = grid
+        .distances4_to([start], &end, |wall| !*wall)
+        .map(|distance| distance.wrapping_add(1) as usize);
+    assert_ne!(*distances.get(&end), 0, "end is not reachable");
     distances
 }
 
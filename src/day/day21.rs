@@ -1,8 +1,8 @@
 use crate::solution::SolutionTuple;
+use crate::util::hash::FxHashMap;
 use crate::util::measure::MeasureContext;
 use crate::util::position::{Direction, Position};
 use arrayvec::ArrayVec;
-use rustc_hash::FxHashMap;
 
 type PreparedInput = Vec<(usize, Vec<u8>)>;
 
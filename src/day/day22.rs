@@ -1,6 +1,7 @@
 use crate::solution::SolutionTuple;
 use crate::util::measure::MeasureContext;
-use std::simd::{LaneCount, Simd, SupportedLaneCount};
+#[cfg(feature = "simd")]
+use std::simd::Simd;
 
 type PreparedInput = Vec<u32>;
 
@@ -8,30 +9,110 @@ fn prepare(input: &str) -> PreparedInput {
     input.lines().map(|l| l.parse().unwrap()).collect()
 }
 
+// Secret numbers are evolved in lanes, several at a time with the simd feature and one at a
+// time otherwise. Only these primitives differ between the two.
+#[cfg(feature = "simd")]
+const LANE: usize = 8;
+#[cfg(feature = "simd")]
+type Lanes = Simd<u32, LANE>;
+#[cfg(feature = "simd")]
+fn splat(value: u32) -> Lanes {
+    Simd::splat(value)
+}
+#[cfg(feature = "simd")]
+fn load(numbers: &[u32]) -> Lanes {
+    Simd::load_or_default(numbers)
+}
+#[cfg(feature = "simd")]
+fn lane(lanes: &Lanes, i: usize) -> u32 {
+    lanes[i]
+}
+
+/// Tracks which windows every lane of the current chunk has seen, with one bit per lane.
+#[cfg(feature = "simd")]
+struct Visited {
+    found: Vec<u8>,
+}
+#[cfg(feature = "simd")]
+impl Visited {
+    fn new(len: usize) -> Self {
+        Self {
+            found: vec![0; len],
+        }
+    }
+    fn next_chunk(&mut self) {
+        self.found.fill(0);
+    }
+    fn first_visit(&mut self, idx: usize, i: usize) -> bool {
+        let found = &mut self.found[idx];
+        let first = (*found >> i) & 1 == 0;
+        *found |= 1 << i;
+        first
+    }
+}
+
+#[cfg(not(feature = "simd"))]
+const LANE: usize = 1;
+#[cfg(not(feature = "simd"))]
+type Lanes = u32;
+#[cfg(not(feature = "simd"))]
+fn splat(value: u32) -> Lanes {
+    value
+}
+#[cfg(not(feature = "simd"))]
+fn load(numbers: &[u32]) -> Lanes {
+    numbers[0]
+}
+#[cfg(not(feature = "simd"))]
+fn lane(lanes: &Lanes, _: usize) -> u32 {
+    *lanes
+}
+
+/// Tracks which windows the current number has seen, by marking entries with the number's index.
+/// The entries are cleared when the index wraps around.
+#[cfg(not(feature = "simd"))]
+struct Visited {
+    found: Vec<u16>,
+    marker: u16,
+}
+#[cfg(not(feature = "simd"))]
+impl Visited {
+    fn new(len: usize) -> Self {
+        Self {
+            found: vec![0; len],
+            marker: 0,
+        }
+    }
+    fn next_chunk(&mut self) {
+        if self.marker == u16::MAX {
+            self.found.fill(0);
+            self.marker = 0;
+        }
+        self.marker += 1;
+    }
+    fn first_visit(&mut self, idx: usize, _: usize) -> bool {
+        let found = &mut self.found[idx];
+        let first = *found != self.marker;
+        *found = self.marker;
+        first
+    }
+}
+
 const PRUNE: u32 = 16777216;
 
 /// Work with an internal representation (with less pruning) to speed up the inner loop.
 /// To convert the internal state to the number, internal_to_number should be used.
-fn evolve_internal<const N: usize>(mut number: Simd<u32, N>) -> Simd<u32, N>
-where
-    LaneCount<N>: SupportedLaneCount,
-{
+fn evolve_internal(mut number: Lanes) -> Lanes {
     number ^= number << 6;
-    number ^= (number % Simd::splat(PRUNE)) >> 5;
+    number ^= (number % splat(PRUNE)) >> 5;
     number ^= number << 11;
 
     number
 }
-fn internal_to_number<const N: usize>(number: Simd<u32, N>) -> Simd<u32, N>
-where
-    LaneCount<N>: SupportedLaneCount,
-{
-    number % Simd::splat(PRUNE)
+fn internal_to_number(number: Lanes) -> Lanes {
+    number % splat(PRUNE)
 }
-fn evolve_iter<const N: usize>(mut number: Simd<u32, N>) -> impl Iterator<Item = Simd<u32, N>>
-where
-    LaneCount<N>: SupportedLaneCount,
-{
+fn evolve_iter(mut number: Lanes) -> impl Iterator<Item = Lanes> {
     std::iter::from_fn(move || {
         number = evolve_internal(number);
         Some(number)
@@ -40,48 +121,36 @@ where
 
 const SECRET_NUMBERS: usize = 2000;
 
-fn price<const N: usize>(number: Simd<u32, N>) -> Simd<u32, N>
-where
-    LaneCount<N>: SupportedLaneCount,
-{
-    number % Simd::splat(10)
+fn price(number: Lanes) -> Lanes {
+    number % splat(10)
 }
 
-const LANE: usize = 8;
-
 fn solve_both(input: &PreparedInput) -> (u64, u16) {
     let mut p1 = 0;
 
     // Implemented using an array. At 2 bytes * 19^4, this uses about 0.25 MB of memory
     let mut map = vec![0u16; 19 * 19 * 19 * 19];
-    let nineteen_simd: Simd<u32, LANE> = Simd::splat(19);
-    let nineteen_simd_2 = nineteen_simd * nineteen_simd;
-    let nineteen_simd_3 = nineteen_simd_2 * nineteen_simd;
-    let eight_byte_mask: Simd<u32, LANE> = Simd::splat((1 << 8) - 1);
 
-    // Pre-allocate a map where each combination maps to an entry.
-    // Each bit in an entry is used for a simd lane.
-    let mut found = vec![0u8; 19 * 19 * 19 * 19];
+    // Pre-allocate a map where each combination maps to an entry
+    let mut visited = Visited::new(19 * 19 * 19 * 19);
 
     input.chunks(LANE).for_each(|chunk| {
-        found.iter_mut().for_each(|elem| *elem = 0);
-
-        let numbers: Simd<u32, LANE> = Simd::load_or_default(chunk);
+        visited.next_chunk();
 
-        let mut last_number = Simd::splat(0);
+        let mut last_number = splat(0);
 
-        let mut prices = evolve_iter(numbers)
+        let mut prices = evolve_iter(load(chunk))
             .take(SECRET_NUMBERS)
             .map(internal_to_number)
             .inspect(|num| last_number = *num)
             .map(price);
 
         let mut previous_price = prices.next().unwrap();
-        let mut window = Simd::splat(0u32);
+        let mut window = splat(0);
         let mut price_window_iter = prices.map(|price| {
             // Price will go from -9 to 9. Map difference from 0..=18
             // This is 5 bytes, shift window by 8 bytes so that its values are shifted out of scope after 4 shifts (32 bytes)
-            let price_diff = price + Simd::splat(9) - previous_price;
+            let price_diff = price + splat(9) - previous_price;
             previous_price = price;
 
             window <<= 8;
@@ -97,29 +166,23 @@ fn solve_both(input: &PreparedInput) -> (u64, u16) {
 
         for (price, window) in price_window_iter {
             // Turn the window using most of the u32 range into something smaller so a dumber map (an array) can be used
-            let window_idx = (window >> 24) * nineteen_simd_3
-                + ((window >> 16) & eight_byte_mask) * nineteen_simd_2
-                + ((window >> 8) & eight_byte_mask) * nineteen_simd
-                + (window & eight_byte_mask);
+            let window_idx = (window >> 24) * splat(19 * 19 * 19)
+                + ((window >> 16) & splat(0xff)) * splat(19 * 19)
+                + ((window >> 8) & splat(0xff)) * splat(19)
+                + (window & splat(0xff));
 
-            for i in 0..chunk.len() {
-                let window_idx = window_idx[i] as usize;
+            // Lanes past the end of the last chunk evolve 0, which has a price of 0 and adds nothing
+            for i in 0..LANE {
+                let window_idx = lane(&window_idx, i) as usize;
 
                 // Only add if the current entry was from a previous item
-                let found = &mut found[window_idx];
-                if (*found >> i) & 1 == 0 {
-                    let entry = &mut map[window_idx];
-                    *entry += price[i] as u16;
-                    *found |= 1 << i;
+                if visited.first_visit(window_idx, i) {
+                    map[window_idx] += lane(&price, i) as u16;
                 }
             }
         }
 
-        p1 += last_number
-            .as_array()
-            .iter()
-            .map(|last_number| *last_number as u64)
-            .sum::<u64>();
+        p1 += (0..LANE).map(|i| lane(&last_number, i) as u64).sum::<u64>();
     });
 
     (p1, map.into_iter().max().unwrap())
@@ -145,10 +208,10 @@ mod tests {
     #[test]
     fn evolve_123() {
         assert_eq!(
-            evolve_iter(Simd::from_array([123]))
+            evolve_iter(load(&[123]))
                 .take(10)
                 .map(internal_to_number)
-                .map(|simd| simd[0])
+                .map(|number| lane(&number, 0))
                 .collect::<Vec<_>>(),
             [
                 15887950, 16495136, 527345, 704524, 1553684, 12683156, 11100544, 12249484, 7753432,
@@ -157,6 +220,15 @@ mod tests {
         );
     }
     #[test]
+    fn visited_many_chunks() {
+        let mut visited = Visited::new(2);
+        for _ in 0..70_000 {
+            visited.next_chunk();
+            assert!(visited.first_visit(1, 0));
+            assert!(!visited.first_visit(1, 0));
+        }
+    }
+    #[test]
     fn part1_example() {
         assert_eq!(solve_both(&prepare(EXAMPLE_PART1)).0, 37327623);
     }
@@ -1,8 +1,8 @@
 use crate::solution::SolutionTuple;
+use crate::util::hash::{FxHashMap, FxHashSet};
 use crate::util::measure::MeasureContext;
 use crate::util::solver::{Stack, solve_priority_dedup};
 use itertools::Itertools;
-use rustc_hash::{FxHashMap, FxHashSet};
 use std::cmp::Ordering;
 use std::fmt::{Display, Formatter};
 use std::ops::ControlFlow;
@@ -1,7 +1,7 @@
 use crate::solution::SolutionTuple;
+use crate::util::hash::{FxHashMap, FxHashSet};
 use crate::util::measure::MeasureContext;
 use itertools::Itertools;
-use rustc_hash::{FxHashMap, FxHashSet};
 use std::array::TryFromSliceError;
 use std::fmt::{Debug, Display, Formatter};
 use std::str::FromStr;
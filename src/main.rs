@@ -1,64 +1,253 @@
+#[allow(unused_imports)] // Nothing is imported when all day features are disabled
 use aoc_2024::day::*;
 use aoc_2024::solution::SolutionTuple;
+use aoc_2024::util::grid::{FrameWriter, ImageFormat};
+use aoc_2024::util::hash;
 use aoc_2024::util::measure::MeasureContext;
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
 use std::hint::black_box;
+use std::ops::Range;
+use std::panic::AssertUnwindSafe;
+use std::path::PathBuf;
 use std::time::{Duration, Instant};
 
+mod scaffold;
+mod watch;
+
 #[derive(Parser, Debug)]
 #[command(author, version, about, long_about = None)]
 struct Args {
+    #[command(subcommand)]
+    command: Option<Command>,
     /// Day
     day: Option<usize>,
     #[arg(short, long, default_value = "1")]
     repeat: u32,
     #[arg(short, long, default_value = "0")]
     warmup: u32,
+    /// Re-solve with randomly seeded hashers and report any answer that changes
+    #[arg(long, value_name = "SEEDS", num_args = 0..=1, default_missing_value = "8")]
+    shuffle_check: Option<u32>,
+}
+
+#[derive(Subcommand, Debug)]
+enum Command {
+    /// Create a new day from the day00 template, including an empty input file
+    New { day: usize },
+    /// Re-solve a day whenever its source or input change
+    Watch { day: usize },
+    /// Write frames of a day's simulation as PPM images into a directory
+    Frames {
+        day: usize,
+        directory: PathBuf,
+        /// First simulation step to write
+        #[arg(long, default_value = "0")]
+        from: usize,
+        /// Number of frames to write
+        #[arg(long, default_value = "100")]
+        count: usize,
+    },
+}
+
+type Solver = fn(&mut MeasureContext, &str) -> SolutionTuple;
+
+fn input_path(day: usize) -> String {
+    format!("./input/day{:0>2}.txt", day)
 }
 
 fn read_input(day: usize) -> String {
-    std::fs::read_to_string(format!("./input/day{:0>2}.txt", day)).unwrap()
+    std::fs::read_to_string(input_path(day)).unwrap()
+}
+
+fn new_day(day: usize) {
+    if let Err(e) = scaffold::new_day(day) {
+        eprintln!("Could not create day{:0>2}: {}", day, e);
+        std::process::exit(1);
+    }
+
+    std::fs::create_dir_all("./input").unwrap();
+    let path = input_path(day);
+    if !std::path::Path::new(&path).exists() {
+        std::fs::write(&path, "").unwrap();
+        println!("Created {}", path);
+    }
+}
+
+type FramesWriter = fn(&str, Range<usize>, &mut FrameWriter) -> std::io::Result<()>;
+
+/// Writes frames of the days that support it, see `FrameWriter`.
+fn write_frames(day: usize, directory: PathBuf, from: usize, count: usize) {
+    let all_frames: &[(usize, FramesWriter)] = &[
+        #[cfg(feature = "day14")]
+        (14, day14::write_frames),
+    ];
+    let Some(&(_, write)) = all_frames.iter().find(|(d, _)| *d == day) else {
+        eprintln!(
+            "day{:0>2} has no frames, or its feature is not enabled",
+            day
+        );
+        std::process::exit(1);
+    };
+
+    let input = read_input(day);
+    let result = FrameWriter::new(&directory, ImageFormat::Ppm)
+        .and_then(|mut frames| write(&input, from..from + count, &mut frames));
+    match result {
+        Ok(()) => println!("Wrote {} frames to {}", count, directory.display()),
+        Err(e) => {
+            eprintln!("Could not write frames of day{:0>2}: {}", day, e);
+            std::process::exit(1);
+        }
+    }
+}
+
+/// Re-solves with randomly seeded hashers, reporting each seed where the solution differs.
+fn shuffle_check(day: usize, solver: Solver, input: &str, solution: &SolutionTuple, seeds: u32) {
+    let mut mismatches = 0;
+    for _ in 0..seeds {
+        let seed = RandomState::new().hash_one(day);
+        hash::set_seed(seed);
+        let result = std::panic::catch_unwind(AssertUnwindSafe(|| {
+            solver(&mut MeasureContext::new(), input)
+        }));
+        hash::set_seed(0);
+
+        match result {
+            Ok(result) if &result == solution => {}
+            Ok(SolutionTuple(p1, p2)) => {
+                mismatches += 1;
+                println!(
+                    "day{}/shuffle_check: seed {:#x} gives {}, {} instead of {}, {}",
+                    day, seed, p1, p2, solution.0, solution.1
+                );
+            }
+            Err(_) => {
+                mismatches += 1;
+                println!("day{}/shuffle_check: seed {:#x} panicked", day, seed);
+            }
+        }
+    }
+    if mismatches == 0 {
+        println!("day{}/shuffle_check: ok ({} seeds)", day, seeds);
+    }
+}
+
+/// Runs the solver as a child process through cargo, so source changes are rebuilt first. Only
+/// the watched day is built, with the other features of this build.
+fn watch_day(day: usize, repeat: u32, warmup: u32) {
+    let cargo = std::env::var("CARGO").unwrap_or_else(|_| "cargo".to_string());
+    let paths = [format!("./src/day/day{:0>2}.rs", day), input_path(day)];
+    let mut features = vec![format!("day{:0>2}", day)];
+    if cfg!(feature = "simd") {
+        features.push("simd".to_string());
+    }
+    if cfg!(feature = "png") {
+        features.push("png".to_string());
+    }
+    let features = features.join(",");
+    watch::watch(day, &paths, || {
+        let mut command = std::process::Command::new(&cargo);
+        command.args([
+            "run",
+            "--quiet",
+            "--no-default-features",
+            "--features",
+            &features,
+        ]);
+        if !cfg!(debug_assertions) {
+            command.arg("--release");
+        }
+        command
+            .args(["--", &day.to_string()])
+            .args(["--repeat", &repeat.to_string()])
+            .args(["--warmup", &warmup.to_string()])
+            .output()
+    });
 }
 
 fn main() {
     let args = Args::parse();
     assert!(args.repeat > 0);
 
-    let all_days = [
-        day01::solve,
-        day02::solve,
-        day03::solve,
-        day04::solve,
-        day05::solve,
-        day06::solve,
-        day07::solve,
-        day08::solve,
-        day09::solve,
-        day10::solve,
-        day11::solve,
-        day12::solve,
-        day13::solve,
-        day14::solve,
-        day15::solve,
-        day16::solve,
-        day17::solve,
-        day18::solve,
-        day19::solve,
-        day20::solve,
-        day21::solve,
-        day22::solve,
-        day23::solve,
-        day24::solve,
-        day25::solve,
+    match args.command {
+        Some(Command::New { day }) => return new_day(day),
+        Some(Command::Watch { day }) => return watch_day(day, args.repeat, args.warmup),
+        Some(Command::Frames {
+            day,
+            directory,
+            from,
+            count,
+        }) => return write_frames(day, directory, from, count),
+        None => {}
+    }
+
+    let all_days: &[(usize, Solver)] = &[
+        #[cfg(feature = "day01")]
+        (1, day01::solve),
+        #[cfg(feature = "day02")]
+        (2, day02::solve),
+        #[cfg(feature = "day03")]
+        (3, day03::solve),
+        #[cfg(feature = "day04")]
+        (4, day04::solve),
+        #[cfg(feature = "day05")]
+        (5, day05::solve),
+        #[cfg(feature = "day06")]
+        (6, day06::solve),
+        #[cfg(feature = "day07")]
+        (7, day07::solve),
+        #[cfg(feature = "day08")]
+        (8, day08::solve),
+        #[cfg(feature = "day09")]
+        (9, day09::solve),
+        #[cfg(feature = "day10")]
+        (10, day10::solve),
+        #[cfg(feature = "day11")]
+        (11, day11::solve),
+        #[cfg(feature = "day12")]
+        (12, day12::solve),
+        #[cfg(feature = "day13")]
+        (13, day13::solve),
+        #[cfg(feature = "day14")]
+        (14, day14::solve),
+        #[cfg(feature = "day15")]
+        (15, day15::solve),
+        #[cfg(feature = "day16")]
+        (16, day16::solve),
+        #[cfg(feature = "day17")]
+        (17, day17::solve),
+        #[cfg(feature = "day18")]
+        (18, day18::solve),
+        #[cfg(feature = "day19")]
+        (19, day19::solve),
+        #[cfg(feature = "day20")]
+        (20, day20::solve),
+        #[cfg(feature = "day21")]
+        (21, day21::solve),
+        #[cfg(feature = "day22")]
+        (22, day22::solve),
+        #[cfg(feature = "day23")]
+        (23, day23::solve),
+        #[cfg(feature = "day24")]
+        (24, day24::solve),
+        #[cfg(feature = "day25")]
+        (25, day25::solve),
     ];
 
     let day_and_solver: Vec<_> = match args.day {
         None => all_days
-            .into_iter()
-            .enumerate()
-            .map(|(i, solve)| (i + 1, solve, read_input(i + 1)))
+            .iter()
+            .map(|&(day, solve)| (day, solve, read_input(day)))
             .collect(),
-        Some(d) => vec![(d, all_days[d - 1], read_input(d))],
+        Some(d) => {
+            let &(_, solve) = all_days
+                .iter()
+                .find(|(day, _)| *day == d)
+                .unwrap_or_else(|| panic!("day{:0>2} is not enabled, enable its feature", d));
+            vec![(d, solve, read_input(d))]
+        }
     };
 
     let total_duration = day_and_solver
@@ -80,7 +269,7 @@ fn main() {
             }
             let end = Instant::now();
 
-            let SolutionTuple(p1, p2) = solution;
+            let SolutionTuple(p1, p2) = &solution;
 
             println!("day{}/part1: {}", day, p1);
             println!("day{}/part2: {}", day, p2);
@@ -102,6 +291,9 @@ fn main() {
                     )
                 }
             );
+            if let Some(seeds) = args.shuffle_check {
+                shuffle_check(day, solver, &input, &solution, seeds);
+            }
             (end - start) / args.repeat
         })
         .sum::<Duration>();
@@ -0,0 +1,196 @@
+use std::fs;
+use std::io::{Error, ErrorKind};
+use std::path::Path;
+
+const TEMPLATE: &str = "./src/day/day00.rs";
+const DAY_MODULES: &str = "./src/day/mod.rs";
+const MAIN: &str = "./src/main.rs";
+const MANIFEST: &str = "./Cargo.toml";
+
+/// Creates `src/day/dayXX.rs` from the day00 template and registers it in `day/mod.rs`, `main.rs`
+/// and `Cargo.toml`. Paths are relative to the repository root. Fails without changing anything
+/// if the module already exists.
+pub fn new_day(day: usize) -> std::io::Result<()> {
+    let name = day_name(day);
+    let module_path = format!("./src/day/{}.rs", name);
+    if Path::new(&module_path).exists() {
+        return Err(Error::new(
+            ErrorKind::AlreadyExists,
+            format!("{} already exists", module_path),
+        ));
+    }
+
+    fs::write(&module_path, fs::read_to_string(TEMPLATE)?)?;
+    println!("Created {}", module_path);
+
+    let modules = fs::read_to_string(DAY_MODULES)?;
+    fs::write(DAY_MODULES, register_module(&modules, day))?;
+    println!("Registered {} in {}", name, DAY_MODULES);
+
+    let main = fs::read_to_string(MAIN)?;
+    fs::write(MAIN, register_solver(&main, day))?;
+    println!("Registered {} in {}", name, MAIN);
+
+    let manifest = fs::read_to_string(MANIFEST)?;
+    fs::write(MANIFEST, register_feature(&manifest, day))?;
+    println!("Registered {} in {}", name, MANIFEST);
+
+    Ok(())
+}
+
+fn day_name(day: usize) -> String {
+    format!("day{:0>2}", day)
+}
+
+/// Rewrites the list of day modules, keeping them sorted.
+fn register_module(modules: &str, day: usize) -> String {
+    let mut days = modules
+        .lines()
+        .filter_map(|line| {
+            line.strip_prefix("pub mod day")?
+                .strip_suffix(';')?
+                .parse()
+                .ok()
+        })
+        .collect::<Vec<usize>>();
+    days.push(day);
+    days.sort_unstable();
+    days.dedup();
+
+    days.into_iter()
+        .map(|day| {
+            let name = day_name(day);
+            format!("#[cfg(feature = \"{}\")]\npub mod {};\n", name, name)
+        })
+        .collect()
+}
+
+/// Appends the solver to the end of the `all_days` list, unless it is already registered.
+fn register_solver(main: &str, day: usize) -> String {
+    let name = day_name(day);
+    if main.contains(&format!("({}, {}::solve),", day, name)) {
+        return main.to_string();
+    }
+    let start = main
+        .find("let all_days")
+        .expect("all_days list not found in main.rs");
+    let end = start
+        + main[start..]
+            .find("\n    ];")
+            .expect("end of all_days list not found in main.rs");
+
+    format!(
+        "{}\n        #[cfg(feature = \"{}\")]\n        ({}, {}::solve),{}",
+        &main[..end],
+        name,
+        day,
+        name,
+        &main[end..]
+    )
+}
+
+/// Adds the feature of the day to `all_days` and declares it after the last day feature, unless it
+/// is already registered.
+fn register_feature(manifest: &str, day: usize) -> String {
+    let name = day_name(day);
+    if manifest.contains(&format!("\n{} = [", name)) {
+        return manifest.to_string();
+    }
+    let list_start = manifest
+        .find("all_days = [")
+        .expect("all_days feature not found in Cargo.toml");
+    let list_end = list_start
+        + manifest[list_start..]
+            .find("\n]")
+            .expect("end of all_days feature not found in Cargo.toml");
+    // The declarations follow the list, up to the end of the features table
+    let declarations_end = manifest[list_end + 1..]
+        .split_inclusive('\n')
+        .scan(list_end + 1, |start, line| {
+            let line_start = *start;
+            *start += line.len();
+            Some((line_start, line.trim_end()))
+        })
+        .take_while(|(_, line)| !line.is_empty() && !line.starts_with('['))
+        .filter(|(_, line)| *line == "]" || is_day_feature(line))
+        .map(|(line_start, line)| line_start + line.len())
+        .last()
+        .expect("end of all_days feature not found in Cargo.toml");
+
+    format!(
+        "{}\n    \"{}\",{}\n{} = []{}",
+        &manifest[..list_end],
+        name,
+        &manifest[list_end..declarations_end],
+        name,
+        &manifest[declarations_end..]
+    )
+}
+
+fn is_day_feature(line: &str) -> bool {
+    line.strip_prefix("day")
+        .and_then(|line| line.strip_suffix(" = []"))
+        .is_some_and(|day| day.parse::<usize>().is_ok())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn register_module_sorted() {
+        assert_eq!(
+            register_module(
+                "#[cfg(feature = \"day01\")]\npub mod day01;\n#[cfg(feature = \"day03\")]\npub mod day03;\n",
+                2
+            ),
+            "#[cfg(feature = \"day01\")]\npub mod day01;\n#[cfg(feature = \"day02\")]\npub mod day02;\n#[cfg(feature = \"day03\")]\npub mod day03;\n"
+        );
+    }
+    #[test]
+    fn register_solver_appended() {
+        assert_eq!(
+            register_solver(
+                "    let all_days: &[(usize, Solver)] = &[\n        #[cfg(feature = \"day01\")]\n        (1, day01::solve),\n    ];\n",
+                2
+            ),
+            "    let all_days: &[(usize, Solver)] = &[\n        #[cfg(feature = \"day01\")]\n        (1, day01::solve),\n        #[cfg(feature = \"day02\")]\n        (2, day02::solve),\n    ];\n"
+        );
+    }
+    #[test]
+    fn register_solver_existing() {
+        let main = "    let all_days: &[(usize, Solver)] = &[\n        #[cfg(feature = \"day01\")]\n        (1, day01::solve),\n    ];\n";
+        assert_eq!(register_solver(main, 1), main);
+    }
+    /// Layout of the features table in Cargo.toml
+    const MANIFEST_FIXTURE: &str = "[features]
+default = [\"all_days\"]
+# Every day is behind its own feature, so a single day can be built with
+# `cargo build --no-default-features --features day05`
+all_days = [
+    \"day01\",
+    \"day02\",
+]
+day01 = []
+day02 = []
+# Use std::simd for day22, requires a nightly toolchain (`cargo +nightly build --features simd`)
+simd = []
+# PNG export for grid images, PPM and SVG need no extra dependencies
+png = [\"dep:png\"]
+
+[dependencies]
+";
+    #[test]
+    fn register_feature_appended() {
+        assert_eq!(
+            register_feature(MANIFEST_FIXTURE, 3),
+            MANIFEST_FIXTURE
+                .replace("    \"day02\",\n", "    \"day02\",\n    \"day03\",\n")
+                .replace("day02 = []\n", "day02 = []\nday03 = []\n")
+        );
+    }
+    #[test]
+    fn register_feature_existing() {
+        assert_eq!(register_feature(MANIFEST_FIXTURE, 1), MANIFEST_FIXTURE);
+    }
+}
@@ -0,0 +1,205 @@
+//! Polygons on the integer lattice, given as vertex lists without repeating the first vertex.
+//! Combining the shoelace formula with Pick's theorem counts the cells of a loop, which is how
+//! lagoon and pipe loop puzzles are solved without filling a grid.
+
+use crate::util::grid::Regions;
+use crate::util::hash::FxHashMap;
+use crate::util::position::{DIRECTIONS, Direction, Point, RotationalDirection};
+use arrayvec::ArrayVec;
+use num::{Integer, Signed};
+
+fn edges<T: Copy>(vertices: &[Point<T>]) -> impl Iterator<Item = (Point<T>, Point<T>)> + '_ {
+    vertices
+        .iter()
+        .zip(vertices.iter().cycle().skip(1))
+        .map(|(a, b)| (*a, *b))
+}
+
+/// Twice the area enclosed by the polygon, positive when the vertices run clockwise on screen,
+/// where y grows downwards.
+pub fn signed_double_area<T: Copy + Signed>(vertices: &[Point<T>]) -> T {
+    edges(vertices).fold(T::zero(), |sum, (a, b)| sum + a.cross(&b))
+}
+
+/// Area enclosed by the polygon, rounded down for polygons with diagonal edges.
+pub fn shoelace_area<T: Copy + Signed + Integer>(vertices: &[Point<T>]) -> T {
+    signed_double_area(vertices).abs() / (T::one() + T::one())
+}
+
+/// Number of lattice points on the edges of the polygon.
+pub fn boundary_points<T: Copy + Signed + Integer>(vertices: &[Point<T>]) -> T {
+    edges(vertices).fold(T::zero(), |sum, (a, b)| {
+        let edge = b - a;
+        sum + edge.0.abs().gcd(&edge.1.abs())
+    })
+}
+
+/// Number of lattice points strictly inside the polygon, by Pick's theorem
+/// `A = I + B / 2 - 1`.
+pub fn interior_points<T: Copy + Signed + Integer>(vertices: &[Point<T>]) -> T {
+    let two = T::one() + T::one();
+    (signed_double_area(vertices).abs() - boundary_points(vertices) + two) / two
+}
+
+fn unit<T: Signed>(direction: &Direction) -> Point<T> {
+    match direction {
+        Direction::Up => Point(-T::one(), T::zero()),
+        Direction::Right => Point(T::zero(), T::one()),
+        Direction::Down => Point(T::one(), T::zero()),
+        Direction::Left => Point(T::zero(), -T::one()),
+    }
+}
+
+/// Vertices visited by a walk of straight segments from `start`, such as a dig plan. The walk is
+/// expected to return to `start`, which is the first vertex.
+pub fn walk_vertices<T: Copy + Signed>(
+    start: Point<T>,
+    steps: impl IntoIterator<Item = (Direction, T)>,
+) -> Vec<Point<T>> {
+    let mut vertices = vec![start];
+    let mut current = start;
+    for (direction, length) in steps {
+        current = current + unit(&direction) * length;
+        vertices.push(current);
+    }
+    if vertices.len() > 1 && vertices.last() == Some(&start) {
+        vertices.pop();
+    }
+    vertices
+}
+
+/// Outlines of a region of `Grid::regions` as rectilinear polygons on the cell corners, where
+/// cell (y, x) spans the corners (y, x) to (y + 1, x + 1). The outer outline runs clockwise and
+/// the outlines of holes anticlockwise, so the signed areas add up to the area of the region.
+/// Where two cells of the region touch only at a corner, the outline turns around each cell on
+/// its own, so the number of vertices matches `Region::corners`.
+pub fn region_polygons(regions: &Regions, label: usize) -> Vec<Vec<Point>> {
+    let labels = &regions.labels;
+    let region = &regions.regions[label];
+
+    // Boundary edges by their start corner, directed to keep the region on their right
+    let mut outgoing: FxHashMap<Point, ArrayVec<Direction, 2>> = FxHashMap::default();
+    for pos in &region.boundary {
+        for direction in DIRECTIONS {
+            let outside = pos
+                .checked_moved(&labels.dimensions, &direction)
+                .is_none_or(|next| *labels.get(&next) != label);
+            if outside {
+                let corner = Point::from(*pos);
+                let (start, edge) = match direction {
+                    Direction::Up => (corner, Direction::Right),
+                    Direction::Right => (Point(corner.0, corner.1 + 1), Direction::Down),
+                    Direction::Down => (Point(corner.0 + 1, corner.1 + 1), Direction::Left),
+                    Direction::Left => (Point(corner.0 + 1, corner.1), Direction::Up),
+                };
+                outgoing.entry(start).or_default().push(edge);
+            }
+        }
+    }
+
+    let mut polygons = Vec::new();
+    while let Some(&start) = outgoing.keys().min() {
+        // The smallest corner has a single outgoing edge and is always a vertex
+        let mut direction = outgoing.remove(&start).unwrap()[0];
+        let mut vertices = vec![start];
+        let mut current = start.moved(&direction);
+        while current != start {
+            let edges = outgoing.get_mut(&current).unwrap();
+            let index = [
+                direction.rotated(&RotationalDirection::Clockwise),
+                direction,
+                direction.rotated(&RotationalDirection::Anticlockwise),
+            ]
+            .into_iter()
+            .find_map(|next| edges.iter().position(|edge| *edge == next))
+            .unwrap();
+            let next = edges.swap_remove(index);
+            if edges.is_empty() {
+                outgoing.remove(&current);
+            }
+            if next != direction {
+                vertices.push(current);
+            }
+            direction = next;
+            current = current.moved(&direction);
+        }
+        polygons.push(vertices);
+    }
+    polygons
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::util::grid::Grid;
+    use crate::util::position::Position;
+
+    #[test]
+    fn lagoon() {
+        let plan = "R 6,D 5,L 2,D 2,R 2,D 2,L 5,U 2,L 1,U 2,R 2,U 3,L 2,U 2";
+        let steps = plan.split(',').map(|step| {
+            let (direction, length) = step.split_once(' ').unwrap();
+            let direction = match direction {
+                "U" => Direction::Up,
+                "R" => Direction::Right,
+                "D" => Direction::Down,
+                _ => Direction::Left,
+            };
+            (direction, length.parse::<i64>().unwrap())
+        });
+        let vertices = walk_vertices(Point(0, 0), steps);
+        assert_eq!(vertices.len(), 14);
+        assert_eq!(shoelace_area(&vertices), 42);
+        assert_eq!(boundary_points(&vertices), 38);
+        assert_eq!(interior_points(&vertices) + boundary_points(&vertices), 62);
+    }
+
+    #[test]
+    fn diagonal_edges() {
+        let triangle = [Point(0, 0), Point(0, 4), Point(3, 0)];
+        assert_eq!(signed_double_area(&triangle), 12);
+        assert_eq!(boundary_points(&triangle), 8);
+        assert_eq!(interior_points(&triangle), 3);
+    }
+
+    #[test]
+    fn regions() {
+        let grid = Grid::from_rows(["AAAA", "ABBA", "ABAA", "AAAC"].map(|row| row.bytes()));
+        let regions = grid.regions(|a, b| a == b);
+        let a = *regions.labels.get(&Position(0, 0));
+        let polygons = region_polygons(&regions, a);
+        assert_eq!(polygons.len(), 2);
+        assert_eq!(
+            polygons
+                .iter()
+                .map(|p| signed_double_area(p))
+                .sum::<isize>(),
+            2 * regions.regions[a].area as isize
+        );
+        assert_eq!(
+            polygons.iter().map(Vec::len).sum::<usize>(),
+            regions.regions[a].corners
+        );
+
+        let b = *regions.labels.get(&Position(1, 1));
+        assert_eq!(
+            region_polygons(&regions, b),
+            [vec![
+                Point(1, 1),
+                Point(1, 3),
+                Point(2, 3),
+                Point(2, 2),
+                Point(3, 2),
+                Point(3, 1)
+            ]]
+        );
+
+        // The cells of A at (1, 1) and (2, 2) touch at a corner, joining the two holes
+        let grid = Grid::from_rows(["AAAA", "AABA", "ABAA", "AAAA"].map(|row| row.bytes()));
+        let regions = grid.regions(|a, b| a == b);
+        let polygons = region_polygons(&regions, 0);
+        assert_eq!(polygons.iter().map(Vec::len).collect::<Vec<_>>(), [4, 8]);
+        assert_eq!(regions.regions[0].corners, 12);
+        assert_eq!(signed_double_area(&polygons[1]), -4);
+    }
+}
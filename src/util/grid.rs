@@ -1,9 +1,38 @@
-use crate::util::position::{Dimensions, Position};
+use crate::util::position::{
+    DIRECTIONS, DIRECTIONS8, Dimensions, Direction, Direction8, Position, PositionOffset,
+};
+use arrayvec::ArrayVec;
 use num::integer::div_rem;
+use std::cmp::Ordering;
+use std::error::Error;
 use std::fmt::{Display, Formatter, Write};
 use std::ops::{Index, IndexMut};
 
-#[derive(Clone, Eq, PartialEq, Hash)]
+mod backed;
+mod bits;
+mod image;
+mod path;
+mod ray;
+mod regions;
+mod render;
+mod sparse;
+mod torus;
+mod transform;
+pub use backed::{BackedGrid, BackedGridMut};
+pub use bits::BitGrid;
+pub use image::{FrameWriter, Image, ImageFormat, Rgb};
+pub use path::{
+    DenseIndices, ShortestPaths, StateIndices, UNREACHABLE, astar, dijkstra, dijkstra_dense,
+    distances4, distances4_to,
+};
+pub use ray::RayCast;
+pub use regions::{Region, Regions};
+pub use render::{Color, Render};
+pub use sparse::SparseGrid;
+pub use torus::{Torus, TorusStorage};
+pub use transform::{SubGrid, Transform, TransformedGrid};
+
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
 pub struct Grid<T> {
     pub dimensions: Dimensions,
     data: Vec<T>,
@@ -113,13 +142,20 @@ impl<T> Grid<T> {
     }
 
     pub fn rows(&self) -> impl Iterator<Item = &[T]> + '_ {
-        self.data.chunks_exact(self.dimensions.1)
+        self.data.chunks_exact(self.dimensions.1.max(1))
     }
 
     pub fn get_row(&self, j: usize) -> &[T] {
         &self.data[j * self.dimensions.1..(j + 1) * self.dimensions.1]
     }
 
+    pub fn map<U>(&self, f: impl FnMut(&T) -> U) -> Grid<U> {
+        Grid {
+            dimensions: self.dimensions,
+            data: self.data.iter().map(f).collect(),
+        }
+    }
+
     pub fn transposed(&self) -> Self
     where
         T: Copy,
@@ -218,6 +254,188 @@ impl Grid<bool> {
     }
 }
 
+/// Conversion from a character of the puzzle input to a grid cell.
+pub trait FromCell: Sized {
+    fn from_cell(cell: u8) -> Option<Self>;
+}
+
+impl FromCell for bool {
+    fn from_cell(cell: u8) -> Option<Self> {
+        match cell {
+            b'#' => Some(true),
+            b'.' => Some(false),
+            _ => None,
+        }
+    }
+}
+
+impl FromCell for u8 {
+    fn from_cell(cell: u8) -> Option<Self> {
+        Some(cell)
+    }
+}
+
+/// Conversion from a grid cell back to its character in the puzzle input, the inverse of
+/// `FromCell`.
+pub trait ToCell {
+    fn to_cell(&self) -> u8;
+}
+
+impl ToCell for bool {
+    fn to_cell(&self) -> u8 {
+        match self {
+            true => b'#',
+            false => b'.',
+        }
+    }
+}
+
+impl ToCell for u8 {
+    fn to_cell(&self) -> u8 {
+        *self
+    }
+}
+
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub enum ParseGridError {
+    UnexpectedCharacter {
+        row: usize,
+        column: usize,
+        character: char,
+    },
+    RowLength {
+        row: usize,
+        expected: usize,
+        actual: usize,
+    },
+}
+
+impl Display for ParseGridError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParseGridError::UnexpectedCharacter {
+                row,
+                column,
+                character,
+            } => write!(
+                f,
+                "unexpected character {:?} at row {}, column {}",
+                character, row, column
+            ),
+            ParseGridError::RowLength {
+                row,
+                expected,
+                actual,
+            } => write!(
+                f,
+                "row {} has length {}, expected {}",
+                row, actual, expected
+            ),
+        }
+    }
+}
+
+impl Error for ParseGridError {}
+
+/// Positions of marker characters (such as `S` and `E`) found while parsing a grid.
+#[derive(Debug, Clone, Default, Eq, PartialEq)]
+pub struct Markers {
+    positions: Vec<(u8, Position)>,
+}
+
+impl Markers {
+    /// Position of the first occurrence of the marker.
+    pub fn get(&self, marker: u8) -> Option<Position> {
+        self.iter(marker).next()
+    }
+
+    pub fn iter(&self, marker: u8) -> impl Iterator<Item = Position> + '_ {
+        self.positions
+            .iter()
+            .filter(move |(m, _)| *m == marker)
+            .map(|(_, pos)| *pos)
+    }
+
+    pub fn insert(&mut self, marker: u8, pos: Position) {
+        self.positions.push((marker, pos));
+    }
+}
+
+impl FromIterator<(u8, Position)> for Markers {
+    fn from_iter<I: IntoIterator<Item = (u8, Position)>>(iter: I) -> Self {
+        Self {
+            positions: iter.into_iter().collect(),
+        }
+    }
+}
+
+impl<T: ToCell> Grid<T> {
+    /// Writes the grid back as puzzle input, one line per row without a trailing newline.
+    /// Markers are written over the cells at their positions, the inverse of `Grid::parse`.
+    pub fn to_text(&self, markers: &Markers) -> String {
+        let mut rows = self
+            .rows()
+            .map(|row| row.iter().map(ToCell::to_cell).collect::<Vec<_>>())
+            .collect::<Vec<_>>();
+        markers
+            .positions
+            .iter()
+            .for_each(|&(marker, pos)| rows[pos.0][pos.1] = marker);
+        rows.into_iter()
+            .map(|row| String::from_utf8(row).expect("cells should be ASCII"))
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+impl<T: FromCell> Grid<T> {
+    /// Parses a grid with one row per line. Characters in `markers` are recorded by position and
+    /// hold the default value in the grid.
+    pub fn parse(input: &str, markers: &[u8]) -> Result<(Self, Markers), ParseGridError>
+    where
+        T: Default,
+    {
+        let mut data = vec![];
+        let mut found_markers = Markers::default();
+        let mut width = None;
+        let mut height = 0;
+
+        for (row, line) in input.lines().enumerate() {
+            let line = line.as_bytes();
+            let expected = *width.get_or_insert(line.len());
+            if line.len() != expected {
+                return Err(ParseGridError::RowLength {
+                    row,
+                    expected,
+                    actual: line.len(),
+                });
+            }
+
+            for (column, &c) in line.iter().enumerate() {
+                if markers.contains(&c) {
+                    found_markers.positions.push((c, Position(row, column)));
+                    data.push(T::default());
+                    continue;
+                }
+                data.push(T::from_cell(c).ok_or(ParseGridError::UnexpectedCharacter {
+                    row,
+                    column,
+                    character: c as char,
+                })?);
+            }
+            height += 1;
+        }
+
+        Ok((
+            Self {
+                dimensions: Dimensions(height, width.unwrap_or(0)),
+                data,
+            },
+            found_markers,
+        ))
+    }
+}
+
 pub trait CellDisplay {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result;
 }
@@ -231,13 +449,19 @@ impl CellDisplay for bool {
     }
 }
 
+impl CellDisplay for u8 {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.write_char(*self as char)
+    }
+}
+
 impl<T> Display for Grid<T>
 where
     T: CellDisplay,
 {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
         self.data
-            .chunks_exact(self.dimensions.1)
+            .chunks_exact(self.dimensions.1.max(1))
             .try_for_each(|row| {
                 row.iter().try_for_each(|value| value.fmt(f))?;
                 f.write_char('\n')
@@ -261,63 +485,108 @@ impl Extend<Position> for Grid<bool> {
     }
 }
 
-pub struct BackedGrid<'a, I> {
-    data: &'a [I],
-    pub dimensions: (usize, usize),
-    row_stride: usize,
-}
+impl<T> Grid<T> {
+    fn is_interior(&self, pos: &Position) -> bool {
+        pos.0 > 0 && pos.1 > 0 && pos.0 + 1 < self.dimensions.0 && pos.1 + 1 < self.dimensions.1
+    }
 
-impl<'a, I> BackedGrid<'a, I> {
-    pub fn from_data_and_row_separator(data: &'a [I], separator: I) -> Self
-    where
-        I: Eq,
-    {
-        let width = data
-            .iter()
-            .position(|value| *value == separator)
-            .unwrap_or(data.len());
-        let row_stride = width + 1;
-        Self {
-            data,
-            dimensions: (data.len().div_ceil(row_stride), width),
-            row_stride,
+    /// Positions next to `pos` in the four directions that are within the grid.
+    /// Owned, so the grid can be modified while iterating.
+    pub fn neighbor_positions4(&self, pos: &Position) -> ArrayVec<(Direction, Position), 4> {
+        let mut neighbors = ArrayVec::new();
+        if self.is_interior(pos) {
+            for direction in DIRECTIONS {
+                // Safety: at most four directions are pushed
+                unsafe { neighbors.push_unchecked((direction, pos.moved(&direction))) };
+            }
+        } else {
+            for direction in DIRECTIONS {
+                if let Some(next) = pos.checked_moved(&self.dimensions, &direction) {
+                    // Safety: at most four directions are pushed
+                    unsafe { neighbors.push_unchecked((direction, next)) };
+                }
+            }
         }
+        neighbors
     }
 
-    fn index(&self, pos: &Position) -> usize {
-        pos.0 * self.row_stride + pos.1
+    /// Positions of all eight surrounding cells that are within the grid, see `neighbor_positions4`.
+    pub fn neighbor_positions8(&self, pos: &Position) -> ArrayVec<(Direction8, Position), 8> {
+        let mut neighbors = ArrayVec::new();
+        if self.is_interior(pos) {
+            for direction in DIRECTIONS8 {
+                // Safety: at most eight directions are pushed
+                unsafe { neighbors.push_unchecked((direction, pos.offset(&(&direction).into()))) };
+            }
+        } else {
+            for direction in DIRECTIONS8 {
+                if let Some(next) = pos.checked_offset(&self.dimensions, &(&direction).into()) {
+                    // Safety: at most eight directions are pushed
+                    unsafe { neighbors.push_unchecked((direction, next)) };
+                }
+            }
+        }
+        neighbors
     }
 
-    pub fn get<T>(&self, pos: &Position) -> T
-    where
-        &'a I: Into<T>,
-    {
-        (&self.data[self.index(pos)]).into()
+    pub fn neighbors4(
+        &self,
+        pos: &Position,
+    ) -> impl DoubleEndedIterator<Item = (Direction, Position, &T)> + '_ {
+        self.neighbor_positions4(pos)
+            .into_iter()
+            // Safety: neighbor positions are within the grid
+            .map(|(direction, next)| (direction, next, unsafe { self.get_unchecked(&next) }))
     }
 
-    pub fn iter<T>(&'a self) -> impl Iterator<Item = (Position, T)>
-    where
-        &'a I: Into<T>,
-    {
-        self.data.iter().enumerate().filter_map(move |(i, value)| {
-            let pair = div_rem(i, self.row_stride);
-            if pair.1 >= self.dimensions.1 {
-                None
-            } else {
-                Some((pair.into(), value.into()))
-            }
-        })
+    pub fn neighbors4_where(
+        &self,
+        pos: &Position,
+        mut f: impl FnMut(&T) -> bool,
+    ) -> impl Iterator<Item = (Direction, Position, &T)> {
+        self.neighbors4(pos).filter(move |(_, _, value)| f(value))
     }
 
-    pub fn positions(&self) -> impl Iterator<Item = Position> {
-        self.data.iter().enumerate().filter_map(move |(i, _)| {
-            let pair = div_rem(i, self.row_stride);
-            if pair.1 >= self.dimensions.1 {
-                None
-            } else {
-                Some(Position(pair.0, pair.1))
-            }
-        })
+    pub fn neighbors8(
+        &self,
+        pos: &Position,
+    ) -> impl DoubleEndedIterator<Item = (Direction8, Position, &T)> + '_ {
+        self.neighbor_positions8(pos)
+            .into_iter()
+            // Safety: neighbor positions are within the grid
+            .map(|(direction, next)| (direction, next, unsafe { self.get_unchecked(&next) }))
+    }
+
+    pub fn neighbors8_where(
+        &self,
+        pos: &Position,
+        mut f: impl FnMut(&T) -> bool,
+    ) -> impl Iterator<Item = (Direction8, Position, &T)> {
+        self.neighbors8(pos).filter(move |(_, _, value)| f(value))
+    }
+
+    pub fn for_each_neighbor4_mut(
+        &mut self,
+        pos: &Position,
+        mut f: impl FnMut(Direction, Position, &mut T),
+    ) {
+        for (direction, next) in self.neighbor_positions4(pos) {
+            let idx = self.index(&next);
+            // Safety: neighbor positions are within the grid
+            f(direction, next, unsafe { self.data.get_unchecked_mut(idx) });
+        }
+    }
+
+    pub fn for_each_neighbor8_mut(
+        &mut self,
+        pos: &Position,
+        mut f: impl FnMut(Direction8, Position, &mut T),
+    ) {
+        for (direction, next) in self.neighbor_positions8(pos) {
+            let idx = self.index(&next);
+            // Safety: neighbor positions are within the grid
+            f(direction, next, unsafe { self.data.get_unchecked_mut(idx) });
+        }
     }
 }
 
@@ -357,8 +626,8 @@ impl<T> GridWindow3<'_, T> {
 
 impl<T> Grid<T> {
     pub fn iter_windows3(&self) -> impl DoubleEndedIterator<Item = GridWindow3<'_, T>> + '_ {
-        (1..self.dimensions.1 - 1).flat_map(move |y| {
-            (1..self.dimensions.0 - 1).map(move |x| GridWindow3 {
+        (1..self.dimensions.0.saturating_sub(1)).flat_map(move |y| {
+            (1..self.dimensions.1.saturating_sub(1)).map(move |x| GridWindow3 {
                 grid: self,
                 idx: y * self.dimensions.1 + x,
             })
@@ -380,6 +649,266 @@ impl<T> Grid<T> {
     }
 }
 
+/// How windows that extend past the border of the grid are handled.
+#[derive(Debug)]
+pub enum WindowEdge<'a, T> {
+    /// Only produce windows that are fully within the grid.
+    Skip,
+    /// Cells outside the grid take the value of the nearest cell within the grid.
+    Clamp,
+    /// Cells outside the grid take the given value.
+    Pad(&'a T),
+}
+
+// Manual implementations, deriving would require T: Copy
+impl<T> Clone for WindowEdge<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+impl<T> Copy for WindowEdge<'_, T> {}
+
+/// A window of `H` rows and `W` columns, anchored on the cell at offset `(H / 2, W / 2)`, which is
+/// the center for odd sizes.
+pub struct GridWindow<'grid, T, const H: usize, const W: usize> {
+    data: &'grid [T],
+    dimensions: Dimensions,
+    row_stride: usize,
+    top: isize,
+    left: isize,
+    edge: WindowEdge<'grid, T>,
+    /// Index of the top left cell if the window is fully within the grid.
+    within: Option<usize>,
+}
+
+impl<'grid, T, const H: usize, const W: usize> GridWindow<'grid, T, H, W> {
+    /// Window over rows of `data` that start every `row_stride` elements.
+    fn new(
+        data: &'grid [T],
+        dimensions: Dimensions,
+        row_stride: usize,
+        (top, left): (isize, isize),
+        edge: WindowEdge<'grid, T>,
+    ) -> Self {
+        let within = (top >= 0
+            && left >= 0
+            && top as usize + H <= dimensions.0
+            && left as usize + W <= dimensions.1)
+            .then(|| top as usize * row_stride + left as usize);
+        Self {
+            data,
+            dimensions,
+            row_stride,
+            top,
+            left,
+            edge,
+            within,
+        }
+    }
+
+    /// Window that is known to be fully within the grid, with its top left cell at `start`.
+    fn new_within(
+        data: &'grid [T],
+        dimensions: Dimensions,
+        row_stride: usize,
+        (top, left): (isize, isize),
+        edge: WindowEdge<'grid, T>,
+        start: usize,
+    ) -> Self {
+        Self {
+            data,
+            dimensions,
+            row_stride,
+            top,
+            left,
+            edge,
+            within: Some(start),
+        }
+    }
+
+    /// Position of the anchor cell.
+    pub fn position(&self) -> Position {
+        Position(
+            (self.top + (H / 2) as isize) as usize,
+            (self.left + (W / 2) as isize) as usize,
+        )
+    }
+
+    pub fn center(&self) -> &'grid T {
+        self.get(H / 2, W / 2)
+    }
+
+    /// Cell at `row` and `column` within the window, counted from its top left.
+    pub fn get(&self, row: usize, column: usize) -> &'grid T {
+        assert!(row < H && column < W);
+        match self.within {
+            Some(start) => &self.data[start + row * self.row_stride + column],
+            None => self.get_past_edge(row, column),
+        }
+    }
+
+    /// Cell of a window that extends past the border of the grid, see `WindowEdge`.
+    #[cold]
+    fn get_past_edge(&self, row: usize, column: usize) -> &'grid T {
+        let y = self.top + row as isize;
+        let x = self.left + column as isize;
+
+        let dimensions = self.dimensions;
+        let (y, x) = match self.edge {
+            WindowEdge::Skip => unreachable!(),
+            WindowEdge::Clamp => (
+                y.clamp(0, dimensions.0 as isize - 1),
+                x.clamp(0, dimensions.1 as isize - 1),
+            ),
+            WindowEdge::Pad(value) => {
+                if y < 0 || x < 0 || y as usize >= dimensions.0 || x as usize >= dimensions.1 {
+                    return value;
+                }
+                (y, x)
+            }
+        };
+        &self.data[y as usize * self.row_stride + x as usize]
+    }
+
+    /// Cell at an offset from the anchor cell.
+    pub fn offset(&self, offset: &PositionOffset) -> &'grid T {
+        let row = (H / 2).checked_add_signed(offset.0).unwrap();
+        let column = (W / 2).checked_add_signed(offset.1).unwrap();
+        self.get(row, column)
+    }
+
+    /// Row of the window as a slice of the grid, `None` if it extends past the border.
+    pub fn row(&self, row: usize) -> Option<&'grid [T]> {
+        assert!(row < H);
+        let y = self.top + row as isize;
+        if y < 0
+            || self.left < 0
+            || y as usize >= self.dimensions.0
+            || self.left as usize + W > self.dimensions.1
+        {
+            return None;
+        }
+        let start = y as usize * self.row_stride + self.left as usize;
+        Some(&self.data[start..start + W])
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &'grid T> + '_ {
+        (0..H).flat_map(move |row| (0..W).map(move |column| self.get(row, column)))
+    }
+}
+
+/// Number of windows and the top left corner of the first one, see `WindowEdge` for which windows
+/// are produced.
+fn window_origins<T, const H: usize, const W: usize>(
+    dimensions: Dimensions,
+    edge: &WindowEdge<'_, T>,
+) -> (Dimensions, isize, isize) {
+    match edge {
+        WindowEdge::Skip => (
+            Dimensions(
+                (dimensions.0 + 1).saturating_sub(H),
+                (dimensions.1 + 1).saturating_sub(W),
+            ),
+            0,
+            0,
+        ),
+        WindowEdge::Clamp | WindowEdge::Pad(_) => {
+            (dimensions, -((H / 2) as isize), -((W / 2) as isize))
+        }
+    }
+}
+
+/// Windows over rows of `data` that start every `row_stride` elements, in row-major order of
+/// their anchor cell.
+fn iter_windows<'grid, T, const H: usize, const W: usize>(
+    data: &'grid [T],
+    dimensions: Dimensions,
+    row_stride: usize,
+    edge: WindowEdge<'grid, T>,
+) -> impl Iterator<Item = GridWindow<'grid, T, H, W>> {
+    let (count, top, left) = window_origins::<T, H, W>(dimensions, &edge);
+    (0..count.0).flat_map(move |y| {
+        (0..count.1).map(move |x| {
+            let origin = (top + y as isize, left + x as isize);
+            GridWindow::new(data, dimensions, row_stride, origin, edge)
+        })
+    })
+}
+
+impl<T> Grid<T> {
+    /// Iterates windows of `H` rows and `W` columns in row-major order of their anchor cell.
+    pub fn iter_windows<'grid, const H: usize, const W: usize>(
+        &'grid self,
+        edge: WindowEdge<'grid, T>,
+    ) -> impl Iterator<Item = GridWindow<'grid, T, H, W>> {
+        iter_windows(&self.data, self.dimensions, self.dimensions.1, edge)
+    }
+
+    /// Iterates the windows whose anchor cell satisfies `f`, which is checked before the window
+    /// is built. The order is the same as for `iter_windows`.
+    pub fn iter_windows_where<'grid, const H: usize, const W: usize>(
+        &'grid self,
+        edge: WindowEdge<'grid, T>,
+        mut f: impl FnMut(&T) -> bool,
+    ) -> impl Iterator<Item = GridWindow<'grid, T, H, W>> {
+        let width = self.dimensions.1;
+        let (count, top, left) = window_origins::<T, H, W>(self.dimensions, &edge);
+        // The anchors form a rectangle of `count` cells within the grid
+        let anchor_top = (top + (H / 2) as isize) as usize;
+        let anchor_left = (left + (W / 2) as isize) as usize;
+        let anchors = if count.0 == 0 || count.1 == 0 {
+            0..0
+        } else {
+            let start = anchor_top * width + anchor_left;
+            start..start + (count.0 - 1) * width + count.1
+        };
+        anchors.filter_map(move |i| {
+            if !f(&self.data[i]) {
+                return None;
+            }
+            let x = i % width;
+            (x >= anchor_left && x < anchor_left + count.1).then(|| {
+                let origin = (
+                    (i / width) as isize - (H / 2) as isize,
+                    x as isize - (W / 2) as isize,
+                );
+                match edge {
+                    // Skipped windows never extend past the border
+                    WindowEdge::Skip => {
+                        let start = i - (H / 2) * width - W / 2;
+                        GridWindow::new_within(
+                            &self.data,
+                            self.dimensions,
+                            width,
+                            origin,
+                            edge,
+                            start,
+                        )
+                    }
+                    _ => GridWindow::new(&self.data, self.dimensions, width, origin, edge),
+                }
+            })
+        })
+    }
+
+    /// Builds a new grid from every window. With `WindowEdge::Skip` the result is smaller than
+    /// the grid, otherwise it has the same dimensions.
+    pub fn map_windows<'grid, U, const H: usize, const W: usize>(
+        &'grid self,
+        edge: WindowEdge<'grid, T>,
+        mut f: impl FnMut(&GridWindow<'grid, T, H, W>) -> U,
+    ) -> Grid<U> {
+        let (dimensions, _, _) = window_origins::<T, H, W>(self.dimensions, &edge);
+        Grid {
+            dimensions,
+            data: self
+                .iter_windows::<H, W>(edge)
+                .map(|window| f(&window))
+                .collect(),
+        }
+    }
+}
+
 impl<T> Grid<T> {
     pub fn columns(&self) -> impl Iterator<Item = impl Iterator<Item = T>>
     where
@@ -391,48 +920,338 @@ impl<T> Grid<T> {
                 .cloned()
         })
     }
+    /// Down-right diagonals starting in the first column.
     pub fn diagonals_lower(&self) -> impl Iterator<Item = impl Iterator<Item = T>>
     where
         T: Clone,
     {
-        let smallest = self.dimensions.0.min(self.dimensions.1);
-        (0..smallest).map(move |y_start| {
-            (0..smallest - y_start)
+        (0..self.dimensions.0).map(move |y_start| {
+            (0..(self.dimensions.0 - y_start).min(self.dimensions.1))
                 .map(move |i| self.get(&Position(y_start + i, i)))
                 .cloned()
         })
     }
+    /// Down-right diagonals starting in the first row.
     pub fn diagonals_upper(&self) -> impl Iterator<Item = impl Iterator<Item = T>>
     where
         T: Clone,
     {
-        let smallest = self.dimensions.0.min(self.dimensions.1);
-        (0..smallest).map(move |x_start| {
-            (0..smallest - x_start)
+        (0..self.dimensions.1).map(move |x_start| {
+            (0..self.dimensions.0.min(self.dimensions.1 - x_start))
                 .map(move |i| self.get(&Position(i, x_start + i)))
                 .cloned()
         })
     }
+    /// Up-right diagonals starting in the first column.
     pub fn anti_diagonals_upper(&self) -> impl Iterator<Item = impl Iterator<Item = T>>
     where
         T: Clone,
     {
-        let smallest = self.dimensions.0.min(self.dimensions.1);
-        (0..smallest).map(move |y_start| {
-            (0..y_start + 1)
+        (0..self.dimensions.0).map(move |y_start| {
+            (0..(y_start + 1).min(self.dimensions.1))
                 .map(move |i| self.get(&Position(y_start - i, i)))
                 .cloned()
         })
     }
+    /// Up-right diagonals starting in the last row.
     pub fn anti_diagonals_lower(&self) -> impl Iterator<Item = impl Iterator<Item = T>>
     where
         T: Clone,
     {
-        let smallest = self.dimensions.0.min(self.dimensions.1);
-        (0..smallest).map(move |x_start| {
-            (0..smallest - x_start)
+        (0..self.dimensions.1).map(move |x_start| {
+            (0..self.dimensions.0.min(self.dimensions.1 - x_start))
                 .map(move |i| self.get(&Position(self.dimensions.0 - 1 - i, x_start + i)))
                 .cloned()
         })
     }
+
+    /// All maximal lines through the grid in the direction of `offset`, each starting at a cell
+    /// whose predecessor is outside the grid. Every cell is part of exactly one line.
+    pub fn lines(
+        &self,
+        offset: PositionOffset,
+    ) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        assert!(offset.0 != 0 || offset.1 != 0);
+        let dimensions = self.dimensions;
+        // Lines start in the rows or columns along the edges that `offset` points away from
+        let entry = |step: isize, size: usize| match step.cmp(&0) {
+            Ordering::Greater => 0..step.unsigned_abs().min(size),
+            Ordering::Less => size.saturating_sub(step.unsigned_abs())..size,
+            Ordering::Equal => 0..0,
+        };
+        let (entry_rows, entry_columns) =
+            (entry(offset.0, dimensions.0), entry(offset.1, dimensions.1));
+        // Number of steps from `pos` before leaving the grid along one axis
+        let steps = |pos: usize, step: isize, size: usize| match step.cmp(&0) {
+            Ordering::Greater => (size - 1 - pos) / step.unsigned_abs(),
+            Ordering::Less => pos / step.unsigned_abs(),
+            Ordering::Equal => usize::MAX,
+        };
+
+        (0..dimensions.0)
+            .flat_map(move |y| {
+                let columns = if entry_rows.contains(&y) {
+                    0..dimensions.1
+                } else {
+                    entry_columns.clone()
+                };
+                columns.map(move |x| Position(y, x))
+            })
+            .map(move |start| {
+                let steps_y = steps(start.0, offset.0, dimensions.0);
+                let steps_x = steps(start.1, offset.1, dimensions.1);
+                let length = steps_y.min(steps_x) + 1;
+                (0..length as isize).map(move |i| {
+                    let pos = Position(
+                        start.0.wrapping_add_signed(offset.0 * i),
+                        start.1.wrapping_add_signed(offset.1 * i),
+                    );
+                    (pos, self.get(&pos))
+                })
+            })
+    }
+
+    /// Down-right diagonals, from the bottom left corner to the top right corner.
+    pub fn diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        let dimensions = self.dimensions;
+        (0..dimensions.0)
+            .rev()
+            .map(|y| Position(y, 0))
+            .chain((1..dimensions.1).map(|x| Position(0, x)))
+            .map(move |start| {
+                let length = (dimensions.0 - start.0).min(dimensions.1 - start.1);
+                (0..length).map(move |i| {
+                    let pos = Position(start.0 + i, start.1 + i);
+                    (pos, self.get(&pos))
+                })
+            })
+    }
+
+    /// Down-left diagonals, from the top left corner to the bottom right corner.
+    pub fn anti_diagonals(&self) -> impl Iterator<Item = impl Iterator<Item = (Position, &T)>> {
+        let dimensions = self.dimensions;
+        (0..dimensions.1)
+            .map(|x| Position(0, x))
+            .chain((1..dimensions.0).map(move |y| Position(y, dimensions.1 - 1)))
+            .map(move |start| {
+                let length = (dimensions.0 - start.0).min(start.1 + 1);
+                (0..length).map(move |i| {
+                    let pos = Position(start.0 + i, start.1 - i);
+                    (pos, self.get(&pos))
+                })
+            })
+    }
+
+    /// Lines in all eight directions, see `lines`. Each line is paired with its direction.
+    pub fn lines8(
+        &self,
+    ) -> impl Iterator<Item = (Direction8, impl Iterator<Item = (Position, &T)>)> {
+        DIRECTIONS8.into_iter().flat_map(move |direction| {
+            self.lines((&direction).into())
+                .map(move |line| (direction, line))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_markers() {
+        let (grid, markers) = Grid::<bool>::parse("#S.\n.E#", b"SE").unwrap();
+        assert_eq!(grid.dimensions, Dimensions(2, 3));
+        assert_eq!(grid.positions_where(|wall| *wall).count(), 2);
+        assert_eq!(markers.get(b'S'), Some(Position(0, 1)));
+        assert_eq!(markers.get(b'E'), Some(Position(1, 1)));
+        assert_eq!(markers.get(b'@'), None);
+    }
+    #[test]
+    fn parse_errors() {
+        assert_eq!(
+            Grid::<bool>::parse("#.\n.x", b"").err(),
+            Some(ParseGridError::UnexpectedCharacter {
+                row: 1,
+                column: 1,
+                character: 'x'
+            })
+        );
+        assert_eq!(
+            Grid::<bool>::parse("#.\n.", b"").err(),
+            Some(ParseGridError::RowLength {
+                row: 1,
+                expected: 2,
+                actual: 1
+            })
+        );
+    }
+    #[test]
+    fn neighbors() {
+        let grid = Grid::from_rows([[0, 1, 2], [3, 4, 5], [6, 7, 8]]);
+        assert_eq!(
+            grid.neighbors4(&Position(1, 1))
+                .map(|(_, _, value)| *value)
+                .collect::<Vec<_>>(),
+            [1, 7, 3, 5]
+        );
+        assert_eq!(
+            grid.neighbors4(&Position(0, 0))
+                .map(|(direction, pos, _)| (direction, pos))
+                .collect::<Vec<_>>(),
+            [
+                (Direction::Down, Position(1, 0)),
+                (Direction::Right, Position(0, 1))
+            ]
+        );
+        assert_eq!(grid.neighbors8(&Position(1, 1)).count(), 8);
+        assert_eq!(
+            grid.neighbors8_where(&Position(2, 2), |value| value % 2 == 0)
+                .map(|(_, _, value)| *value)
+                .collect::<Vec<_>>(),
+            [4]
+        );
+    }
+    #[test]
+    fn neighbors_mut() {
+        let mut grid = Grid::from_dimensions(Dimensions(2, 3), 0);
+        grid.for_each_neighbor8_mut(&Position(0, 0), |_, _, value| *value += 1);
+        assert_eq!(grid.values().sum::<i32>(), 3);
+    }
+    #[test]
+    fn windows3_rectangular() {
+        let grid = Grid::from_rows([[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]);
+        assert_eq!(
+            grid.iter_windows3()
+                .map(|window| *window.center())
+                .collect::<Vec<_>>(),
+            [5, 6]
+        );
+    }
+    #[test]
+    fn windows_edges() {
+        let grid = Grid::from_rows([[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]);
+        let skip = grid
+            .iter_windows::<2, 3>(WindowEdge::Skip)
+            .collect::<Vec<_>>();
+        assert_eq!(skip.len(), 4);
+        assert_eq!(skip[3].position(), Position(2, 2));
+        assert_eq!(skip[3].row(0), Some([5, 6, 7].as_slice()));
+        assert_eq!(*skip[3].offset(&PositionOffset(-1, -1)), 5);
+
+        let clamp = grid
+            .iter_windows::<3, 3>(WindowEdge::Clamp)
+            .collect::<Vec<_>>();
+        assert_eq!(clamp.len(), 12);
+        assert_eq!(
+            clamp[0].values().copied().collect::<Vec<_>>(),
+            [0, 0, 1, 0, 0, 1, 4, 4, 5]
+        );
+        assert_eq!(clamp[0].row(1), None);
+
+        let padded =
+            grid.map_windows::<_, 3, 3>(WindowEdge::Pad(&0), |window| window.values().sum::<i32>());
+        assert_eq!(padded.dimensions, grid.dimensions);
+        assert_eq!(*padded.get(&Position(0, 0)), 10);
+        assert_eq!(*padded.get(&Position(1, 1)), 45);
+    }
+    #[test]
+    fn windows_where() {
+        let grid = Grid::from_rows([[0, 1, 2, 3], [4, 5, 6, 7], [8, 9, 10, 11]]);
+        let positions = |windows: Vec<GridWindow<'_, i32, 3, 2>>| {
+            windows.iter().map(GridWindow::position).collect::<Vec<_>>()
+        };
+        let odd = |value: &i32| value % 2 == 1;
+        for edge in [WindowEdge::Skip, WindowEdge::Clamp, WindowEdge::Pad(&0)] {
+            assert_eq!(
+                positions(grid.iter_windows_where(edge, odd).collect()),
+                positions(
+                    grid.iter_windows(edge)
+                        .filter(|window| odd(window.center()))
+                        .collect()
+                )
+            );
+        }
+        assert_eq!(
+            positions(grid.iter_windows_where(WindowEdge::Skip, odd).collect()),
+            [Position(1, 1), Position(1, 3)]
+        );
+        let last = grid
+            .iter_windows_where::<3, 2>(WindowEdge::Skip, odd)
+            .last();
+        assert_eq!(
+            last.unwrap().values().copied().collect::<Vec<_>>(),
+            [2, 3, 6, 7, 10, 11]
+        );
+        assert_eq!(
+            grid.iter_windows_where::<4, 1>(WindowEdge::Skip, |_| true)
+                .count(),
+            0
+        );
+    }
+    #[test]
+    fn diagonals_rectangular() {
+        let grid = Grid::from_rows([[0, 1, 2, 3], [4, 5, 6, 7]]);
+        let collect = |lines: Vec<Vec<(Position, &i32)>>| {
+            lines
+                .into_iter()
+                .map(|line| line.into_iter().map(|(_, v)| *v).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(
+            collect(grid.diagonals().map(Iterator::collect).collect()),
+            [vec![4], vec![0, 5], vec![1, 6], vec![2, 7], vec![3]]
+        );
+        assert_eq!(
+            collect(grid.anti_diagonals().map(Iterator::collect).collect()),
+            [vec![0], vec![1, 4], vec![2, 5], vec![3, 6], vec![7]]
+        );
+        assert_eq!(
+            collect(
+                grid.lines(PositionOffset(-1, 1))
+                    .map(Iterator::collect)
+                    .collect()
+            ),
+            [vec![0], vec![4, 1], vec![5, 2], vec![6, 3], vec![7]]
+        );
+        assert_eq!(
+            collect(
+                grid.lines(PositionOffset(1, -2))
+                    .map(Iterator::collect)
+                    .collect()
+            ),
+            [vec![0], vec![1], vec![2, 4], vec![3, 5], vec![6], vec![7]]
+        );
+        assert_eq!(
+            grid.diagonals_lower().map(Iterator::count).sum::<usize>()
+                + grid
+                    .diagonals_upper()
+                    .skip(1)
+                    .map(Iterator::count)
+                    .sum::<usize>(),
+            8
+        );
+        assert_eq!(
+            grid.anti_diagonals_upper()
+                .map(Iterator::count)
+                .sum::<usize>()
+                + grid
+                    .anti_diagonals_lower()
+                    .skip(1)
+                    .map(Iterator::count)
+                    .sum::<usize>(),
+            8
+        );
+        assert_eq!(grid.lines8().flat_map(|(_, line)| line).count(), 8 * 8);
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let input = "#S.\n.#.\n.E#";
+        let (grid, markers) = Grid::<bool>::parse(input, b"SE").unwrap();
+        assert_eq!(grid.to_text(&markers), input);
+        assert_eq!(
+            grid.to_text(&[(b'@', Position(1, 0))].into_iter().collect()),
+            "#..\n@#.\n..#"
+        );
+    }
 }
@@ -0,0 +1,243 @@
+use crate::util::grid::{Grid, GridWindow, WindowEdge, iter_windows};
+use crate::util::position::{DIRECTIONS, DIRECTIONS8, Dimensions, Direction, Direction8, Position};
+use num::integer::div_rem;
+
+/// Dimensions and row stride of data with rows separated by `separator`, such as the raw input.
+/// A trailing separator is allowed.
+fn layout<I: Eq>(data: &[I], separator: &I) -> (Dimensions, usize) {
+    let width = data
+        .iter()
+        .position(|value| value == separator)
+        .unwrap_or(data.len());
+    let row_stride = width + 1;
+    (
+        Dimensions(data.len().div_ceil(row_stride), width),
+        row_stride,
+    )
+}
+
+/// Index of a position in the data. Checked in release builds as well, as a column past the width
+/// would otherwise read the separator or the start of the next row.
+#[inline]
+fn index(dimensions: Dimensions, row_stride: usize, pos: &Position) -> usize {
+    assert!(
+        pos.0 < dimensions.0 && pos.1 < dimensions.1,
+        "{:?} is outside of {:?}",
+        pos,
+        dimensions
+    );
+    pos.0 * row_stride + pos.1
+}
+
+/// Grid view on borrowed data with a separator after every row, which avoids copying the input.
+#[derive(Debug, Clone, Copy)]
+pub struct BackedGrid<'a, I> {
+    data: &'a [I],
+    pub dimensions: Dimensions,
+    row_stride: usize,
+}
+
+impl<'a, I> BackedGrid<'a, I> {
+    pub fn from_data_and_row_separator(data: &'a [I], separator: I) -> Self
+    where
+        I: Eq,
+    {
+        let (dimensions, row_stride) = layout(data, &separator);
+        Self {
+            data,
+            dimensions,
+            row_stride,
+        }
+    }
+
+    fn index(&self, pos: &Position) -> usize {
+        index(self.dimensions, self.row_stride, pos)
+    }
+
+    pub fn get(&self, pos: &Position) -> &'a I {
+        &self.data[self.index(pos)]
+    }
+
+    pub fn get_row(&self, y: usize) -> &'a [I] {
+        let start = y * self.row_stride;
+        &self.data[start..start + self.dimensions.1]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'a [I]> + '_ {
+        (0..self.dimensions.0).map(|y| self.get_row(y))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &'a I)> + '_ {
+        self.data.iter().enumerate().filter_map(move |(i, value)| {
+            let pair = div_rem(i, self.row_stride);
+            (pair.1 < self.dimensions.1).then(|| (pair.into(), value))
+        })
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.iter().map(|(pos, _)| pos)
+    }
+
+    pub fn positions_where(&self, mut f: impl FnMut(&I) -> bool) -> impl Iterator<Item = Position> {
+        self.iter()
+            .filter_map(move |(pos, value)| f(value).then_some(pos))
+    }
+
+    /// Neighbors in the four directions that are within the grid.
+    pub fn neighbors4(
+        &self,
+        pos: &Position,
+    ) -> impl Iterator<Item = (Direction, Position, &'a I)> + '_ {
+        let pos = *pos;
+        DIRECTIONS.into_iter().filter_map(move |direction| {
+            let next = pos.checked_moved(&self.dimensions, &direction)?;
+            Some((direction, next, self.get(&next)))
+        })
+    }
+
+    /// Neighbors in all eight directions that are within the grid.
+    pub fn neighbors8(
+        &self,
+        pos: &Position,
+    ) -> impl Iterator<Item = (Direction8, Position, &'a I)> + '_ {
+        let pos = *pos;
+        DIRECTIONS8.into_iter().filter_map(move |direction| {
+            let next = pos.checked_offset(&self.dimensions, &(&direction).into())?;
+            Some((direction, next, self.get(&next)))
+        })
+    }
+
+    /// Iterates windows of `H` rows and `W` columns, see `Grid::iter_windows`.
+    pub fn iter_windows<const H: usize, const W: usize>(
+        &self,
+        edge: WindowEdge<'a, I>,
+    ) -> impl Iterator<Item = GridWindow<'a, I, H, W>> {
+        iter_windows(self.data, self.dimensions, self.row_stride, edge)
+    }
+
+    /// Copies the cells into an owned grid, leaving out the separators.
+    pub fn to_grid(&self) -> Grid<I>
+    where
+        I: Clone,
+    {
+        Grid::from_rows(self.rows().map(|row| row.iter().cloned()))
+    }
+}
+
+/// Mutable grid view on borrowed data with a separator after every row. Use `as_grid` for the
+/// read-only methods of `BackedGrid`.
+#[derive(Debug)]
+pub struct BackedGridMut<'a, I> {
+    data: &'a mut [I],
+    pub dimensions: Dimensions,
+    row_stride: usize,
+}
+
+impl<'a, I> BackedGridMut<'a, I> {
+    pub fn from_data_and_row_separator(data: &'a mut [I], separator: I) -> Self
+    where
+        I: Eq,
+    {
+        let (dimensions, row_stride) = layout(data, &separator);
+        Self {
+            data,
+            dimensions,
+            row_stride,
+        }
+    }
+
+    pub fn as_grid(&self) -> BackedGrid<'_, I> {
+        BackedGrid {
+            data: self.data,
+            dimensions: self.dimensions,
+            row_stride: self.row_stride,
+        }
+    }
+
+    fn index(&self, pos: &Position) -> usize {
+        index(self.dimensions, self.row_stride, pos)
+    }
+
+    pub fn get(&self, pos: &Position) -> &I {
+        &self.data[self.index(pos)]
+    }
+
+    pub fn get_mut(&mut self, pos: &Position) -> &mut I {
+        let index = self.index(pos);
+        &mut self.data[index]
+    }
+
+    pub fn set(&mut self, pos: &Position, value: I) {
+        *self.get_mut(pos) = value;
+    }
+
+    pub fn get_row_mut(&mut self, y: usize) -> &mut [I] {
+        let start = y * self.row_stride;
+        &mut self.data[start..start + self.dimensions.1]
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (Position, &mut I)> {
+        let (row_stride, width) = (self.row_stride, self.dimensions.1);
+        self.data
+            .iter_mut()
+            .enumerate()
+            .filter_map(move |(i, value)| {
+                let pair = div_rem(i, row_stride);
+                (pair.1 < width).then(|| (pair.into(), value))
+            })
+    }
+
+    pub fn to_grid(&self) -> Grid<I>
+    where
+        I: Clone,
+    {
+        self.as_grid().to_grid()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backed() {
+        let grid = BackedGrid::from_data_and_row_separator(b"abc\ndef\n".as_slice(), b'\n');
+        assert_eq!(grid.dimensions, Dimensions(2, 3));
+        assert_eq!(*grid.get(&Position(1, 2)), b'f');
+        assert_eq!(grid.get_row(1), b"def");
+        assert_eq!(grid.iter().count(), 6);
+        assert_eq!(
+            grid.neighbors4(&Position(0, 1))
+                .map(|(_, _, value)| *value)
+                .collect::<Vec<_>>(),
+            b"eac"
+        );
+        assert_eq!(grid.neighbors8(&Position(1, 1)).count(), 5);
+        assert_eq!(
+            grid.iter_windows::<2, 2>(WindowEdge::Skip)
+                .map(|window| *window.get(1, 1))
+                .collect::<Vec<_>>(),
+            b"ef"
+        );
+        assert_eq!(grid.to_grid().to_text(&Default::default()), "abc\ndef");
+    }
+
+    #[test]
+    #[should_panic]
+    fn get_past_width() {
+        BackedGrid::from_data_and_row_separator(b"abc\ndef".as_slice(), b'\n').get(&Position(0, 3));
+    }
+
+    #[test]
+    fn backed_mut() {
+        let mut data = b"ab\ncd".to_vec();
+        let mut grid = BackedGridMut::from_data_and_row_separator(data.as_mut_slice(), b'\n');
+        grid.set(&Position(1, 0), b'x');
+        grid.iter_mut()
+            .filter(|(pos, _)| pos.1 == 1)
+            .for_each(|(_, value)| *value = b'y');
+        assert_eq!(grid.as_grid().get_row(1), b"xy");
+        assert_eq!(grid.to_grid().dimensions, Dimensions(2, 2));
+        assert_eq!(data, b"ay\nxy");
+    }
+}
@@ -0,0 +1,297 @@
+use crate::util::grid::{CellDisplay, Grid};
+use crate::util::position::{Dimensions, Direction, Position};
+use std::fmt::{Display, Formatter, Write};
+use std::ops::{BitAndAssign, BitOrAssign, BitXorAssign};
+
+/// Set of positions within a grid, with each row packed into `u64` words. Bits past the width
+/// of a row are kept zero, so whole words can be combined and counted directly.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct BitGrid {
+    pub dimensions: Dimensions,
+    row_words: usize,
+    data: Vec<u64>,
+}
+
+impl BitGrid {
+    pub fn new(dimensions: Dimensions) -> Self {
+        let row_words = dimensions.1.div_ceil(64);
+        Self {
+            dimensions,
+            row_words,
+            data: vec![0; dimensions.0 * row_words],
+        }
+    }
+
+    pub fn from_positions<I: IntoIterator<Item = Position>>(
+        dimensions: Dimensions,
+        iter: I,
+    ) -> Self {
+        let mut grid = Self::new(dimensions);
+        grid.extend(iter);
+        grid
+    }
+
+    /// Word and bit of a position. Checked in release builds as well, as a column past the width
+    /// would otherwise touch the zeroed bits at the end of a row.
+    #[inline]
+    fn index(&self, pos: &Position) -> (usize, u64) {
+        assert!(
+            pos.0 < self.dimensions.0 && pos.1 < self.dimensions.1,
+            "{:?} is outside of {:?}",
+            pos,
+            self.dimensions
+        );
+        (pos.0 * self.row_words + pos.1 / 64, 1 << (pos.1 % 64))
+    }
+
+    /// Mask of the bits in the last word of a row that are within the grid.
+    fn last_word_mask(&self) -> u64 {
+        match self.dimensions.1 % 64 {
+            0 => u64::MAX,
+            bits => (1 << bits) - 1,
+        }
+    }
+
+    pub fn contains(&self, pos: &Position) -> bool {
+        let (index, bit) = self.index(pos);
+        self.data[index] & bit != 0
+    }
+
+    pub fn insert(&mut self, pos: &Position) -> bool {
+        let (index, bit) = self.index(pos);
+        let previous = self.data[index];
+        self.data[index] |= bit;
+        previous & bit == 0
+    }
+
+    pub fn remove(&mut self, pos: &Position) -> bool {
+        let (index, bit) = self.index(pos);
+        let previous = self.data[index];
+        self.data[index] &= !bit;
+        previous & bit != 0
+    }
+
+    pub fn set(&mut self, pos: &Position, value: bool) {
+        if value {
+            self.insert(pos);
+        } else {
+            self.remove(pos);
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.data.fill(0);
+    }
+
+    /// Number of positions in the set.
+    pub fn count(&self) -> usize {
+        self.data
+            .iter()
+            .map(|word| word.count_ones() as usize)
+            .sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.iter().all(|word| *word == 0)
+    }
+
+    /// Words of a single row, the first word holds the leftmost 64 columns.
+    pub fn row_words(&self, y: usize) -> &[u64] {
+        &self.data[y * self.row_words..(y + 1) * self.row_words]
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = Position> + '_ {
+        self.data
+            .chunks_exact(self.row_words.max(1))
+            .enumerate()
+            .flat_map(|(y, row)| {
+                row.iter().enumerate().flat_map(move |(i, &word)| {
+                    let mut word = word;
+                    std::iter::from_fn(move || {
+                        if word == 0 {
+                            return None;
+                        }
+                        let offset = word.trailing_zeros() as usize;
+                        word &= word - 1;
+                        Some(Position(y, i * 64 + offset))
+                    })
+                })
+            })
+    }
+
+    fn combine(&mut self, other: &BitGrid, f: impl Fn(&mut u64, u64)) {
+        assert_eq!(self.dimensions, other.dimensions);
+        self.data
+            .iter_mut()
+            .zip(other.data.iter())
+            .for_each(|(word, other)| f(word, *other));
+    }
+
+    /// Removes all positions that are in `other`.
+    pub fn remove_all(&mut self, other: &BitGrid) {
+        self.combine(other, |word, other| *word &= !other);
+    }
+
+    /// Moves every position one step in the given direction. Positions that would leave the grid
+    /// are dropped.
+    pub fn shift(&mut self, direction: Direction) {
+        let row_words = self.row_words;
+        if row_words == 0 || self.dimensions.0 == 0 {
+            return;
+        }
+        match direction {
+            Direction::Up => {
+                self.data.copy_within(row_words.., 0);
+                let len = self.data.len();
+                self.data[len - row_words..].fill(0);
+            }
+            Direction::Down => {
+                let len = self.data.len();
+                self.data.copy_within(..len - row_words, row_words);
+                self.data[..row_words].fill(0);
+            }
+            Direction::Left => self.data.chunks_exact_mut(row_words).for_each(|row| {
+                for i in 0..row_words {
+                    let carry = row.get(i + 1).map_or(0, |next| next << 63);
+                    row[i] = (row[i] >> 1) | carry;
+                }
+            }),
+            Direction::Right => {
+                let mask = self.last_word_mask();
+                self.data.chunks_exact_mut(row_words).for_each(|row| {
+                    for i in (0..row_words).rev() {
+                        let carry = if i > 0 { row[i - 1] >> 63 } else { 0 };
+                        row[i] = (row[i] << 1) | carry;
+                    }
+                    row[row_words - 1] &= mask;
+                })
+            }
+        }
+    }
+
+    #[must_use]
+    pub fn shifted(&self, direction: Direction) -> Self {
+        let mut grid = self.clone();
+        grid.shift(direction);
+        grid
+    }
+
+    pub fn to_grid(&self) -> Grid<bool> {
+        let mut grid = Grid::from_dimensions(self.dimensions, false);
+        grid.extend(self.positions());
+        grid
+    }
+}
+
+impl From<&Grid<bool>> for BitGrid {
+    fn from(grid: &Grid<bool>) -> Self {
+        Self::from_positions(grid.dimensions, grid.positions_where(|value| *value))
+    }
+}
+
+impl Extend<Position> for BitGrid {
+    fn extend<I: IntoIterator<Item = Position>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|pos| {
+            self.insert(&pos);
+        });
+    }
+}
+
+impl BitOrAssign<&BitGrid> for BitGrid {
+    fn bitor_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |word, other| *word |= other);
+    }
+}
+
+impl BitAndAssign<&BitGrid> for BitGrid {
+    fn bitand_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |word, other| *word &= other);
+    }
+}
+
+impl BitXorAssign<&BitGrid> for BitGrid {
+    fn bitxor_assign(&mut self, rhs: &BitGrid) {
+        self.combine(rhs, |word, other| *word ^= other);
+    }
+}
+
+impl Display for BitGrid {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        (0..self.dimensions.0).try_for_each(|y| {
+            (0..self.dimensions.1)
+                .try_for_each(|x| CellDisplay::fmt(&self.contains(&Position(y, x)), f))?;
+            f.write_char('\n')
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn set_operations() {
+        let dimensions = Dimensions(3, 70);
+        let mut a = BitGrid::from_positions(dimensions, [Position(0, 0), Position(1, 64)]);
+        let b = BitGrid::from_positions(dimensions, [Position(1, 64), Position(2, 69)]);
+        assert!(!a.insert(&Position(0, 0)));
+        assert!(a.contains(&Position(1, 64)));
+        assert!(!a.contains(&Position(1, 63)));
+
+        let mut or = a.clone();
+        or |= &b;
+        assert_eq!(or.count(), 3);
+        let mut and = a.clone();
+        and &= &b;
+        assert_eq!(and.positions().collect::<Vec<_>>(), [Position(1, 64)]);
+        a ^= &b;
+        assert_eq!(
+            a.positions().collect::<Vec<_>>(),
+            [Position(0, 0), Position(2, 69)]
+        );
+        a.remove_all(&or);
+        assert!(a.is_empty());
+    }
+
+    #[test]
+    #[should_panic]
+    fn insert_past_width() {
+        BitGrid::new(Dimensions(2, 3)).insert(&Position(0, 3));
+    }
+    #[test]
+    fn shifts() {
+        let dimensions = Dimensions(2, 65);
+        let grid = BitGrid::from_positions(dimensions, [Position(0, 63), Position(1, 64)]);
+        assert_eq!(
+            grid.shifted(Direction::Right)
+                .positions()
+                .collect::<Vec<_>>(),
+            [Position(0, 64)]
+        );
+        assert_eq!(
+            grid.shifted(Direction::Left)
+                .positions()
+                .collect::<Vec<_>>(),
+            [Position(0, 62), Position(1, 63)]
+        );
+        assert_eq!(
+            grid.shifted(Direction::Down)
+                .positions()
+                .collect::<Vec<_>>(),
+            [Position(1, 63)]
+        );
+        assert_eq!(
+            grid.shifted(Direction::Up).positions().collect::<Vec<_>>(),
+            [Position(0, 64)]
+        );
+    }
+
+    #[test]
+    fn dense_round_trip() {
+        let (dense, _) = Grid::<bool>::parse("#..\n.#.\n..#", &[]).unwrap();
+        let bits = BitGrid::from(&dense);
+        assert_eq!(bits.count(), 3);
+        assert_eq!(bits.to_string(), dense.to_string());
+        assert_eq!(bits.to_grid().count(), 3);
+    }
+}
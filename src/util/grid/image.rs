@@ -0,0 +1,240 @@
+use crate::util::grid::Grid;
+use crate::util::position::Dimensions;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+pub type Rgb = [u8; 3];
+
+/// Grid rendered to colors, one pixel per cell. `scale` sets the size of a cell in the output.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Image {
+    pub dimensions: Dimensions,
+    pixels: Vec<Rgb>,
+    scale: usize,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum ImageFormat {
+    Ppm,
+    Svg,
+    #[cfg(feature = "png")]
+    Png,
+}
+
+impl ImageFormat {
+    pub fn extension(&self) -> &'static str {
+        match self {
+            ImageFormat::Ppm => "ppm",
+            ImageFormat::Svg => "svg",
+            #[cfg(feature = "png")]
+            ImageFormat::Png => "png",
+        }
+    }
+
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "ppm" => Some(ImageFormat::Ppm),
+            "svg" => Some(ImageFormat::Svg),
+            #[cfg(feature = "png")]
+            "png" => Some(ImageFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn to_image(&self, color: impl FnMut(&T) -> Rgb) -> Image {
+        Image {
+            dimensions: self.dimensions,
+            pixels: self.data.iter().map(color).collect(),
+            scale: 1,
+        }
+    }
+}
+
+impl Image {
+    #[must_use]
+    pub fn scaled(mut self, scale: usize) -> Self {
+        assert!(scale > 0);
+        self.scale = scale;
+        self
+    }
+
+    /// Rows of the scaled image as RGB bytes.
+    fn scaled_rows(&self) -> impl Iterator<Item = Vec<u8>> + '_ {
+        self.pixels
+            .chunks_exact(self.dimensions.1.max(1))
+            .take(self.dimensions.0)
+            .flat_map(|row| {
+                let bytes = row
+                    .iter()
+                    .flat_map(|pixel| std::iter::repeat_n(pixel, self.scale).flatten())
+                    .copied()
+                    .collect::<Vec<_>>();
+                std::iter::repeat_n(bytes, self.scale)
+            })
+    }
+
+    /// Binary PPM (P6), readable by most image viewers.
+    pub fn write_ppm(&self, mut w: impl Write) -> io::Result<()> {
+        write!(
+            w,
+            "P6\n{} {}\n255\n",
+            self.dimensions.1 * self.scale,
+            self.dimensions.0 * self.scale
+        )?;
+        self.scaled_rows().try_for_each(|row| w.write_all(&row))
+    }
+
+    /// SVG with a rectangle for each horizontal run of equally colored cells.
+    pub fn write_svg(&self, mut w: impl Write) -> io::Result<()> {
+        let (height, width) = (
+            self.dimensions.0 * self.scale,
+            self.dimensions.1 * self.scale,
+        );
+        writeln!(
+            w,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{width}" height="{height}" viewBox="0 0 {width} {height}" shape-rendering="crispEdges">"#
+        )?;
+        for (y, row) in self
+            .pixels
+            .chunks_exact(self.dimensions.1.max(1))
+            .take(self.dimensions.0)
+            .enumerate()
+        {
+            let mut x = 0;
+            for run in row.chunk_by(|a, b| a == b) {
+                let [r, g, b] = run[0];
+                writeln!(
+                    w,
+                    r##"<rect x="{}" y="{}" width="{}" height="{}" fill="#{:02x}{:02x}{:02x}"/>"##,
+                    x * self.scale,
+                    y * self.scale,
+                    run.len() * self.scale,
+                    self.scale,
+                    r,
+                    g,
+                    b
+                )?;
+                x += run.len();
+            }
+        }
+        writeln!(w, "</svg>")
+    }
+
+    #[cfg(feature = "png")]
+    pub fn write_png(&self, w: impl Write) -> io::Result<()> {
+        let mut encoder = png::Encoder::new(
+            w,
+            (self.dimensions.1 * self.scale) as u32,
+            (self.dimensions.0 * self.scale) as u32,
+        );
+        encoder.set_color(png::ColorType::Rgb);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(io::Error::other)?;
+        writer
+            .write_image_data(&self.scaled_rows().flatten().collect::<Vec<_>>())
+            .map_err(io::Error::other)
+    }
+
+    pub fn write(&self, w: impl Write, format: ImageFormat) -> io::Result<()> {
+        match format {
+            ImageFormat::Ppm => self.write_ppm(w),
+            ImageFormat::Svg => self.write_svg(w),
+            #[cfg(feature = "png")]
+            ImageFormat::Png => self.write_png(w),
+        }
+    }
+
+    /// Writes to a file, in the format given by its extension.
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        let path = path.as_ref();
+        let format = ImageFormat::from_path(path).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("unsupported image format: {}", path.display()),
+            )
+        })?;
+        let mut file = io::BufWriter::new(std::fs::File::create(path)?);
+        self.write(&mut file, format)?;
+        file.flush()
+    }
+}
+
+/// Writes numbered frames of a simulation into a directory, `frame_00000.ppm` and onwards.
+pub struct FrameWriter {
+    directory: PathBuf,
+    format: ImageFormat,
+    next: usize,
+}
+
+impl FrameWriter {
+    pub fn new(directory: impl Into<PathBuf>, format: ImageFormat) -> io::Result<Self> {
+        let directory = directory.into();
+        std::fs::create_dir_all(&directory)?;
+        Ok(Self {
+            directory,
+            format,
+            next: 0,
+        })
+    }
+
+    /// Writes the next frame and returns its path.
+    pub fn write(&mut self, image: &Image) -> io::Result<PathBuf> {
+        let path = self.directory.join(format!(
+            "frame_{:0>5}.{}",
+            self.next,
+            self.format.extension()
+        ));
+        image.save(&path)?;
+        self.next += 1;
+        Ok(path)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn image() -> Image {
+        let (grid, _) = Grid::<bool>::parse("#.\n##", &[]).unwrap();
+        grid.to_image(|wall| if *wall { [255, 0, 0] } else { [0, 0, 0] })
+    }
+
+    #[test]
+    fn ppm() {
+        let mut buf = Vec::new();
+        image().scaled(2).write_ppm(&mut buf).unwrap();
+        assert!(buf.starts_with(b"P6\n4 4\n255\n"));
+        assert_eq!(buf.len(), 11 + 4 * 4 * 3);
+        assert_eq!(&buf[11..17], &[255, 0, 0, 255, 0, 0]);
+    }
+
+    #[test]
+    fn svg() {
+        let mut buf = Vec::new();
+        image().write_svg(&mut buf).unwrap();
+        let svg = String::from_utf8(buf).unwrap();
+        assert_eq!(svg.matches("<rect").count(), 3);
+        assert!(svg.contains(r##"<rect x="0" y="1" width="2" height="1" fill="#ff0000"/>"##));
+    }
+
+    #[cfg(feature = "png")]
+    #[test]
+    fn png() {
+        let mut buf = Vec::new();
+        image().scaled(3).write_png(&mut buf).unwrap();
+        assert!(buf.starts_with(b"\x89PNG"));
+    }
+
+    #[test]
+    fn frames() {
+        let directory = std::env::temp_dir().join(format!("aoc-frames-{}", std::process::id()));
+        let mut frames = FrameWriter::new(&directory, ImageFormat::Svg).unwrap();
+        frames.write(&image()).unwrap();
+        let path = frames.write(&image()).unwrap();
+        assert_eq!(path, directory.join("frame_00001.svg"));
+        assert!(path.exists());
+        std::fs::remove_dir_all(directory).unwrap();
+    }
+}
@@ -0,0 +1,422 @@
+use crate::util::grid::Grid;
+use crate::util::hash::{FxHashMap, FxHashSet};
+use crate::util::position::{DIRECTIONS, Dimensions, Position};
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, VecDeque};
+use std::hash::Hash;
+
+/// Distance of positions that cannot be reached in `distances4`.
+pub const UNREACHABLE: u32 = u32::MAX;
+
+/// Breadth first distances from the starts to every reachable position, moving in the four
+/// directions over positions for which `passable` returns true. Other positions are
+/// `UNREACHABLE`.
+pub fn distances4(
+    dimensions: Dimensions,
+    starts: impl IntoIterator<Item = Position>,
+    passable: impl FnMut(&Position) -> bool,
+) -> Grid<u32> {
+    breadth_first4(dimensions, starts, passable, None)
+}
+
+/// Same as `distances4`, but stops as soon as the distance of `target` is known. Positions that
+/// are further away than the target may be left `UNREACHABLE`.
+pub fn distances4_to(
+    dimensions: Dimensions,
+    starts: impl IntoIterator<Item = Position>,
+    target: &Position,
+    passable: impl FnMut(&Position) -> bool,
+) -> Grid<u32> {
+    breadth_first4(dimensions, starts, passable, Some(*target))
+}
+
+fn breadth_first4(
+    dimensions: Dimensions,
+    starts: impl IntoIterator<Item = Position>,
+    mut passable: impl FnMut(&Position) -> bool,
+    target: Option<Position>,
+) -> Grid<u32> {
+    let mut distances = Grid::from_dimensions(dimensions, UNREACHABLE);
+    let mut queue = VecDeque::new();
+    for start in starts {
+        distances.set(&start, 0);
+        queue.push_back(start);
+    }
+    if target.is_some_and(|target| *distances.get(&target) != UNREACHABLE) {
+        return distances;
+    }
+    while let Some(pos) = queue.pop_front() {
+        let distance = distances.get(&pos) + 1;
+        for direction in &DIRECTIONS {
+            let Some(next) = pos.checked_moved(&dimensions, direction) else {
+                continue;
+            };
+            if *distances.get(&next) == UNREACHABLE && passable(&next) {
+                distances.set(&next, distance);
+                if target == Some(next) {
+                    return distances;
+                }
+                queue.push_back(next);
+            }
+        }
+    }
+    distances
+}
+
+impl<T> Grid<T> {
+    /// Breadth first distances from the starts over cells for which `passable` returns true.
+    pub fn distances4(
+        &self,
+        starts: impl IntoIterator<Item = Position>,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> Grid<u32> {
+        distances4(self.dimensions, starts, |pos| passable(self.get(pos)))
+    }
+
+    /// Breadth first distances from the starts until the distance of `target` is known, see
+    /// `distances4_to`.
+    pub fn distances4_to(
+        &self,
+        starts: impl IntoIterator<Item = Position>,
+        target: &Position,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> Grid<u32> {
+        distances4_to(self.dimensions, starts, target, |pos| {
+            passable(self.get(pos))
+        })
+    }
+
+    /// One shortest path between two positions over cells for which `passable` returns true,
+    /// including both ends.
+    pub fn shortest_path4(
+        &self,
+        start: Position,
+        end: Position,
+        mut passable: impl FnMut(&T) -> bool,
+    ) -> Option<Vec<Position>> {
+        astar(
+            [start],
+            |pos| {
+                self.neighbors4(pos)
+                    .filter(|(_, _, value)| passable(value))
+                    .map(|(_, next, _)| (next, 1))
+                    .collect::<Vec<_>>()
+            },
+            |pos| pos.manhattan_distance(&end),
+            |pos| *pos == end,
+        )
+        .path()
+    }
+}
+
+/// Lookup of the index of a state in the tables of a search.
+pub trait StateIndices<S> {
+    /// Index of `state`, which is `next` if the state was not seen before.
+    fn intern(&mut self, state: &S, next: usize) -> usize;
+    fn get(&self, state: &S) -> Option<usize>;
+}
+
+impl<S: Hash + Eq + Clone> StateIndices<S> for FxHashMap<S, usize> {
+    fn intern(&mut self, state: &S, next: usize) -> usize {
+        *self.entry(state.clone()).or_insert(next)
+    }
+    fn get(&self, state: &S) -> Option<usize> {
+        FxHashMap::get(self, state).copied()
+    }
+}
+
+/// Indices for states that map to a small range of keys themselves, such as positions packed
+/// with a direction, kept in a `Vec` instead of a map.
+pub struct DenseIndices<F> {
+    key: F,
+    indices: Vec<usize>,
+}
+
+impl<F> DenseIndices<F> {
+    /// Indices for keys in `0..len`.
+    pub fn new(len: usize, key: F) -> Self {
+        Self {
+            key,
+            indices: vec![NONE; len],
+        }
+    }
+}
+
+impl<S, F: Fn(&S) -> usize> StateIndices<S> for DenseIndices<F> {
+    fn intern(&mut self, state: &S, next: usize) -> usize {
+        let index = &mut self.indices[(self.key)(state)];
+        if *index == NONE {
+            *index = next;
+        }
+        *index
+    }
+    fn get(&self, state: &S) -> Option<usize> {
+        Some(self.indices[(self.key)(state)]).filter(|&index| index != NONE)
+    }
+}
+
+/// Marks a missing index or the end of a predecessor list.
+const NONE: usize = usize::MAX;
+
+/// Result of a shortest path search. Every state remembers all predecessors it can be reached
+/// from at its lowest cost, so both a single path and all optimal paths can be recovered.
+pub struct ShortestPaths<S, M = FxHashMap<S, usize>> {
+    states: Vec<S>,
+    costs: Vec<usize>,
+    /// First entry of the predecessor list of each state in `predecessor_links`.
+    predecessors: Vec<usize>,
+    /// Predecessor lists of all states, as pairs of a state and the next entry.
+    predecessor_links: Vec<(usize, usize)>,
+    indices: M,
+    goals: Vec<usize>,
+}
+
+impl<S, M: StateIndices<S>> ShortestPaths<S, M> {
+    fn new(indices: M) -> Self {
+        Self {
+            states: Vec::new(),
+            costs: Vec::new(),
+            predecessors: Vec::new(),
+            predecessor_links: Vec::new(),
+            indices,
+            goals: Vec::new(),
+        }
+    }
+
+    fn intern(&mut self, state: S) -> usize {
+        let index = self.indices.intern(&state, self.states.len());
+        if index == self.states.len() {
+            self.states.push(state);
+            self.costs.push(usize::MAX);
+            self.predecessors.push(NONE);
+        }
+        index
+    }
+
+    fn predecessors_of(&self, index: usize) -> impl Iterator<Item = usize> + '_ {
+        let mut link = self.predecessors[index];
+        std::iter::from_fn(move || {
+            let (previous, next) = *self.predecessor_links.get(link)?;
+            link = next;
+            Some(previous)
+        })
+    }
+
+    /// Cost of the shortest path, `None` when no goal is reachable.
+    pub fn cost(&self) -> Option<usize> {
+        self.goals.first().map(|&goal| self.costs[goal])
+    }
+
+    /// Goal states that are reached at the lowest cost.
+    pub fn goals(&self) -> impl Iterator<Item = &S> {
+        self.goals.iter().map(|&goal| &self.states[goal])
+    }
+
+    /// Lowest known cost of a state. Only final for states that were expanded before the search
+    /// stopped.
+    pub fn cost_of(&self, state: &S) -> Option<usize> {
+        self.indices.get(state).map(|index| self.costs[index])
+    }
+
+    /// One shortest path from a start to a goal, including both.
+    pub fn path(&self) -> Option<Vec<S>>
+    where
+        S: Clone,
+    {
+        let mut index = *self.goals.first()?;
+        let mut path = vec![self.states[index].clone()];
+        while let Some(previous) = self.predecessors_of(index).next() {
+            index = previous;
+            path.push(self.states[index].clone());
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// All states on any of the shortest paths, in arbitrary order.
+    pub fn optimal_states(&self) -> impl Iterator<Item = &S> {
+        let mut visited = vec![false; self.states.len()];
+        let mut stack = self.goals.clone();
+        stack.iter().for_each(|&goal| visited[goal] = true);
+        std::iter::from_fn(move || {
+            let index = stack.pop()?;
+            for previous in self.predecessors_of(index) {
+                if !visited[previous] {
+                    visited[previous] = true;
+                    stack.push(previous);
+                }
+            }
+            Some(&self.states[index])
+        })
+    }
+
+    /// Positions on any of the shortest paths, for states that carry more than a position.
+    pub fn optimal_positions(&self, position: impl Fn(&S) -> Position) -> FxHashSet<Position> {
+        self.optimal_states().map(position).collect()
+    }
+}
+
+/// Dijkstra's algorithm, see `astar`.
+pub fn dijkstra<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> ShortestPaths<S>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    astar(starts, successors, |_| 0, is_goal)
+}
+
+/// Dijkstra's algorithm for states that `key` maps to `0..len`, which keeps the per-state tables
+/// in vectors instead of hashing every state. See `astar`.
+pub fn dijkstra_dense<S, I, F>(
+    len: usize,
+    key: F,
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    is_goal: impl FnMut(&S) -> bool,
+) -> ShortestPaths<S, DenseIndices<F>>
+where
+    S: Clone,
+    I: IntoIterator<Item = (S, usize)>,
+    F: Fn(&S) -> usize,
+{
+    search(
+        DenseIndices::new(len, key),
+        starts,
+        successors,
+        |_| 0,
+        is_goal,
+    )
+}
+
+/// A* search from the starts until every goal state at the lowest cost has been found.
+/// `successors` returns the next states with the cost of the step. The heuristic has to be
+/// consistent, and step costs non-zero, for the predecessors to cover all optimal paths.
+pub fn astar<S, I>(
+    starts: impl IntoIterator<Item = S>,
+    successors: impl FnMut(&S) -> I,
+    heuristic: impl FnMut(&S) -> usize,
+    is_goal: impl FnMut(&S) -> bool,
+) -> ShortestPaths<S>
+where
+    S: Hash + Eq + Clone,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    search(FxHashMap::default(), starts, successors, heuristic, is_goal)
+}
+
+fn search<S, M, I>(
+    indices: M,
+    starts: impl IntoIterator<Item = S>,
+    mut successors: impl FnMut(&S) -> I,
+    mut heuristic: impl FnMut(&S) -> usize,
+    mut is_goal: impl FnMut(&S) -> bool,
+) -> ShortestPaths<S, M>
+where
+    S: Clone,
+    M: StateIndices<S>,
+    I: IntoIterator<Item = (S, usize)>,
+{
+    let mut paths = ShortestPaths::new(indices);
+
+    let mut heap = BinaryHeap::new();
+    for start in starts {
+        let estimate = heuristic(&start);
+        let index = paths.intern(start);
+        paths.costs[index] = 0;
+        heap.push(Reverse((estimate, 0, index)));
+    }
+
+    let mut best = None;
+    while let Some(Reverse((estimate, cost, index))) = heap.pop() {
+        if best.is_some_and(|best| estimate > best) {
+            break;
+        }
+        // Stale entries of states that were reached at a lower cost since, only the entry with
+        // the lowest cost of each state is expanded
+        if cost > paths.costs[index] {
+            continue;
+        }
+
+        let state = paths.states[index].clone();
+        if is_goal(&state) {
+            best = Some(cost);
+            paths.goals.push(index);
+            continue;
+        }
+        for (next, step) in successors(&state) {
+            let next_cost = cost + step;
+            let next_estimate = next_cost + heuristic(&next);
+            let next_index = paths.intern(next);
+            match next_cost.cmp(&paths.costs[next_index]) {
+                std::cmp::Ordering::Less => {
+                    paths.costs[next_index] = next_cost;
+                    // Entries of the previous, costlier list are left unused
+                    paths.predecessors[next_index] = paths.predecessor_links.len();
+                    paths.predecessor_links.push((index, NONE));
+                    heap.push(Reverse((next_estimate, next_cost, next_index)));
+                }
+                std::cmp::Ordering::Equal => {
+                    let next_link = paths.predecessors[next_index];
+                    paths.predecessors[next_index] = paths.predecessor_links.len();
+                    paths.predecessor_links.push((index, next_link));
+                }
+                std::cmp::Ordering::Greater => {}
+            }
+        }
+    }
+    paths
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+.....
+.###.
+.....
+##.##
+.....";
+
+    #[test]
+    fn bfs_distances() {
+        let (grid, _) = Grid::<bool>::parse(MAZE, &[]).unwrap();
+        let distances = grid.distances4([Position(0, 0)], |wall| !*wall);
+        assert_eq!(*distances.get(&Position(0, 4)), 4);
+        assert_eq!(*distances.get(&Position(4, 0)), 8);
+        assert_eq!(*distances.get(&Position(1, 1)), UNREACHABLE);
+
+        let distances = grid.distances4_to([Position(0, 0)], &Position(2, 0), |wall| !*wall);
+        assert_eq!(*distances.get(&Position(2, 0)), 2);
+        assert_eq!(*distances.get(&Position(4, 0)), UNREACHABLE);
+    }
+
+    #[test]
+    fn paths() {
+        let (grid, _) = Grid::<bool>::parse(MAZE, &[]).unwrap();
+        let path = grid
+            .shortest_path4(Position(0, 0), Position(4, 4), |wall| !*wall)
+            .unwrap();
+        assert_eq!(path.len(), 9);
+        assert_eq!(path.first(), Some(&Position(0, 0)));
+        assert_eq!(path.last(), Some(&Position(4, 4)));
+
+        // Around the block on either side
+        let paths = dijkstra(
+            [Position(0, 0)],
+            |pos| {
+                grid.neighbors4_where(pos, |wall| !*wall)
+                    .map(|(_, next, _)| (next, 1))
+                    .collect::<Vec<_>>()
+            },
+            |pos| *pos == Position(2, 4),
+        );
+        assert_eq!(paths.cost(), Some(6));
+        assert_eq!(paths.optimal_states().count(), 12);
+        assert_eq!(paths.cost_of(&Position(2, 0)), Some(2));
+    }
+}
@@ -0,0 +1,152 @@
+use crate::util::grid::Grid;
+use crate::util::position::{DIRECTIONS8, Direction8, Position, PositionOffset};
+use num::integer::gcd;
+
+/// Outcome of `Grid::cast_ray`.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub struct RayCast {
+    /// Last cell reached, which is the start if the ray stopped right away.
+    pub last: Position,
+    /// Cell that stopped the ray, `None` if the ray left the grid.
+    pub hit: Option<Position>,
+    /// Number of steps from the start to `last`.
+    pub steps: usize,
+}
+
+impl<T> Grid<T> {
+    /// Cells reached by repeatedly adding `offset` to `from`, up to the border of the grid. The
+    /// start itself is not included.
+    pub fn ray(
+        &self,
+        from: &Position,
+        offset: &PositionOffset,
+    ) -> impl Iterator<Item = (Position, &T)> {
+        from.positions_steps(&self.dimensions, offset)
+            .map(|pos| (pos, self.get(&pos)))
+    }
+
+    /// Follows the ray from `from` until `stop` holds for a cell or the ray leaves the grid.
+    pub fn cast_ray(
+        &self,
+        from: &Position,
+        offset: &PositionOffset,
+        mut stop: impl FnMut(&Position, &T) -> bool,
+    ) -> RayCast {
+        let mut cast = RayCast {
+            last: *from,
+            hit: None,
+            steps: 0,
+        };
+        for (pos, value) in self.ray(from, offset) {
+            if stop(&pos, value) {
+                cast.hit = Some(pos);
+                break;
+            }
+            cast.last = pos;
+            cast.steps += 1;
+        }
+        cast
+    }
+
+    /// First cell in the direction of `offset` that is an obstacle.
+    pub fn next_obstacle(
+        &self,
+        from: &Position,
+        offset: &PositionOffset,
+        mut is_obstacle: impl FnMut(&T) -> bool,
+    ) -> Option<Position> {
+        self.cast_ray(from, offset, |_, value| is_obstacle(value))
+            .hit
+    }
+
+    /// Whether no cell strictly between `from` and `to` blocks the view. Only cells whose centers
+    /// lie exactly on the line between the two can block it.
+    pub fn is_visible(
+        &self,
+        from: &Position,
+        to: &Position,
+        mut blocks: impl FnMut(&T) -> bool,
+    ) -> bool {
+        let offset = *to - *from;
+        let steps = gcd(offset.0, offset.1);
+        if steps <= 1 {
+            return true;
+        }
+        let step = offset / steps;
+        (1..steps).all(|i| !blocks(self.get(&(*from + step * i))))
+    }
+
+    /// First cell in each of the eight directions for which `seen` holds, skipping over the
+    /// cells in between.
+    pub fn first_visible8(
+        &self,
+        pos: &Position,
+        mut seen: impl FnMut(&T) -> bool,
+    ) -> impl Iterator<Item = (Direction8, Position, &T)> {
+        let pos = *pos;
+        DIRECTIONS8.into_iter().filter_map(move |direction| {
+            self.ray(&pos, &(&direction).into())
+                .find(|(_, value)| seen(value))
+                .map(|(next, value)| (direction, next, value))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u8> {
+        Grid::from_rows(["..#..", ".....", "#.L.#", ".....", "..#.#"].map(|row| row.bytes()))
+    }
+
+    #[test]
+    fn rays() {
+        let grid = grid();
+        let center = Position(2, 2);
+        assert_eq!(
+            grid.cast_ray(&center, &PositionOffset::up(), |_, value| *value == b'#'),
+            RayCast {
+                last: Position(1, 2),
+                hit: Some(Position(0, 2)),
+                steps: 1
+            }
+        );
+        assert_eq!(
+            grid.cast_ray(&center, &PositionOffset(1, -1), |_, value| *value == b'#'),
+            RayCast {
+                last: Position(4, 0),
+                hit: None,
+                steps: 2
+            }
+        );
+        assert_eq!(
+            grid.next_obstacle(&Position(0, 0), &PositionOffset::right(), |value| *value
+                == b'#'),
+            Some(Position(0, 2))
+        );
+        assert_eq!(
+            grid.first_visible8(&center, |value| *value == b'#')
+                .map(|(direction, _, _)| direction)
+                .collect::<Vec<_>>(),
+            [
+                Direction8::Up,
+                Direction8::Right,
+                Direction8::DownRight,
+                Direction8::Down,
+                Direction8::Left
+            ]
+        );
+    }
+
+    #[test]
+    fn visibility() {
+        let grid = grid();
+        let blocks = |value: &u8| *value != b'.';
+        assert!(!grid.is_visible(&Position(0, 2), &Position(4, 2), blocks));
+        assert!(grid.is_visible(&Position(0, 2), &Position(2, 4), blocks));
+        assert!(grid.is_visible(&Position(0, 2), &Position(4, 4), blocks));
+        assert!(!grid.is_visible(&Position(2, 0), &Position(2, 4), blocks));
+        assert!(grid.is_visible(&Position(2, 2), &Position(2, 2), blocks));
+    }
+}
@@ -0,0 +1,172 @@
+use crate::util::grid::Grid;
+use crate::util::position::{Direction, Position, PositionOffset};
+
+/// Connected component of a grid, see `Grid::regions`.
+#[derive(Debug, Clone, Eq, PartialEq)]
+pub struct Region {
+    pub area: usize,
+    /// Number of cell edges between the region and anything else, including the grid border.
+    pub perimeter: usize,
+    /// Number of corners of the outline, including the outlines of holes.
+    pub corners: usize,
+    /// Top left and bottom right corner of the bounding box, both inclusive.
+    pub bounds: (Position, Position),
+    /// Cells of the region with at least one of their four neighbors outside the region.
+    pub boundary: Vec<Position>,
+}
+
+impl Region {
+    /// Number of straight sides of the outline, which always equals the number of corners.
+    pub fn sides(&self) -> usize {
+        self.corners
+    }
+}
+
+/// Labeled connected components. `labels` holds the index into `regions` for each cell.
+#[derive(Debug, Clone)]
+pub struct Regions {
+    pub labels: Grid<usize>,
+    pub regions: Vec<Region>,
+}
+
+impl Regions {
+    pub fn region(&self, pos: &Position) -> &Region {
+        &self.regions[*self.labels.get(pos)]
+    }
+}
+
+fn find(parents: &mut [usize], mut index: usize) -> usize {
+    while parents[index] != index {
+        parents[index] = parents[parents[index]];
+        index = parents[index];
+    }
+    index
+}
+
+/// Pairs of orthogonal directions around each corner of a cell.
+const CORNERS: [(Direction, Direction); 4] = [
+    (Direction::Up, Direction::Right),
+    (Direction::Right, Direction::Down),
+    (Direction::Down, Direction::Left),
+    (Direction::Left, Direction::Up),
+];
+
+impl<T> Grid<T> {
+    /// Splits the grid into regions of four-connected cells for which `eq` holds between
+    /// neighbors. Cells are labeled with a union-find pass, after which every cell adds its own
+    /// area, edges and corners to its region, so the work is linear in the size of the grid.
+    /// Edges and corners are found by comparing the regions of the cells, so they match the
+    /// regions even if `eq` is not transitive. Regions are ordered by their first cell in
+    /// row-major order.
+    pub fn regions(&self, mut eq: impl FnMut(&T, &T) -> bool) -> Regions {
+        let width = self.dimensions.1;
+        let mut parents = (0..self.data.len()).collect::<Vec<_>>();
+        for index in 0..self.data.len() {
+            if index % width != 0 && eq(&self.data[index], &self.data[index - 1]) {
+                let (a, b) = (find(&mut parents, index), find(&mut parents, index - 1));
+                parents[a.max(b)] = a.min(b);
+            }
+            if index >= width && eq(&self.data[index], &self.data[index - width]) {
+                let (a, b) = (find(&mut parents, index), find(&mut parents, index - width));
+                parents[a.max(b)] = a.min(b);
+            }
+        }
+
+        for index in 0..parents.len() {
+            parents[index] = find(&mut parents, index);
+        }
+        let roots = parents;
+
+        let mut labels = Grid::from_dimensions(self.dimensions, usize::MAX);
+        let mut regions: Vec<Region> = Vec::new();
+        for (index, pos) in self.positions().enumerate() {
+            let root = roots[index];
+            let label = if root == index {
+                regions.push(Region {
+                    area: 0,
+                    perimeter: 0,
+                    corners: 0,
+                    bounds: (pos, pos),
+                    boundary: Vec::new(),
+                });
+                regions.len() - 1
+            } else {
+                // The root is the first cell of the region, so it is labeled already
+                labels.data[root]
+            };
+            labels.data[index] = label;
+
+            let same = |offset: PositionOffset| {
+                pos.checked_offset(&self.dimensions, &offset)
+                    .is_some_and(|neighbor| roots[neighbor.0 * width + neighbor.1] == root)
+            };
+            let region = &mut regions[label];
+            region.area += 1;
+            region.bounds.0.1 = region.bounds.0.1.min(pos.1);
+            region.bounds.1 = Position(pos.0, region.bounds.1.1.max(pos.1));
+
+            let mut edges = 0;
+            for (first, second) in CORNERS {
+                let first_offset: PositionOffset = (&first).into();
+                let second_offset: PositionOffset = (&second).into();
+                let (first_same, second_same) = (same(first_offset), same(second_offset));
+                if !first_same {
+                    edges += 1;
+                }
+                if (!first_same && !second_same)
+                    || (first_same
+                        && second_same
+                        && !same(PositionOffset(
+                            first_offset.0 + second_offset.0,
+                            first_offset.1 + second_offset.1,
+                        )))
+                {
+                    region.corners += 1;
+                }
+            }
+            region.perimeter += edges;
+            if edges > 0 {
+                region.boundary.push(pos);
+            }
+        }
+
+        Regions { labels, regions }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn regions() {
+        let grid = Grid::from_rows(["AAAA", "ABBA", "ABAA", "AAAC"].map(|row| row.bytes()));
+        let regions = grid.regions(|a, b| a == b);
+        assert_eq!(regions.regions.len(), 3);
+
+        let a = regions.region(&Position(0, 0));
+        assert_eq!(a.area, 12);
+        assert_eq!(a.perimeter, 24);
+        assert_eq!(a.sides(), 6 + 6);
+        assert_eq!(a.bounds, (Position(0, 0), Position(3, 3)));
+        assert_eq!(a.boundary.len(), 12);
+
+        let b = regions.region(&Position(2, 1));
+        assert_eq!((b.area, b.perimeter, b.sides()), (3, 8, 6));
+        assert_eq!(b.bounds, (Position(1, 1), Position(2, 2)));
+        assert_eq!(*regions.labels.get(&Position(3, 3)), 2);
+    }
+    #[test]
+    fn non_transitive() {
+        // 0 and 2 are in the same region through the 1s, without being equal themselves
+        let grid = Grid::from_rows([[0, 2], [1, 1]]);
+        let regions = grid.regions(|a: &i32, b| a.abs_diff(*b) <= 1);
+        assert_eq!(regions.regions.len(), 1);
+        let region = &regions.regions[0];
+        assert_eq!((region.area, region.perimeter, region.corners), (4, 8, 4));
+
+        let grid = Grid::from_rows([[0, 1], [1, 2]]);
+        let regions = grid.regions(|a: &i32, b| a.abs_diff(*b) <= 1);
+        assert_eq!(regions.regions[0].corners, 4);
+    }
+}
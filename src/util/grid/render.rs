@@ -0,0 +1,219 @@
+use crate::util::grid::{CellDisplay, Grid};
+use crate::util::hash::FxHashSet;
+use crate::util::position::{Dimensions, Position};
+use std::fmt::{Display, Formatter, Write};
+
+/// Terminal color, written as an ANSI escape sequence.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Color {
+    Black,
+    Red,
+    Green,
+    Yellow,
+    Blue,
+    Magenta,
+    Cyan,
+    White,
+    Gray,
+    Rgb(u8, u8, u8),
+}
+
+impl Color {
+    fn write_escape(&self, f: &mut Formatter<'_>, background: bool) -> std::fmt::Result {
+        let base = if background { 40 } else { 30 };
+        let code = match self {
+            Color::Black => base,
+            Color::Red => base + 1,
+            Color::Green => base + 2,
+            Color::Yellow => base + 3,
+            Color::Blue => base + 4,
+            Color::Magenta => base + 5,
+            Color::Cyan => base + 6,
+            Color::White => base + 7,
+            Color::Gray => base + 60,
+            Color::Rgb(r, g, b) => return write!(f, "\x1b[{};2;{};{};{}m", base + 8, r, g, b),
+        };
+        write!(f, "\x1b[{}m", code)
+    }
+}
+
+type CellColors<'a, T> = Box<dyn Fn(&Position, &T) -> Option<Color> + 'a>;
+
+/// Annotated rendering of a grid, created by `Grid::render`. Markers take precedence over the
+/// path, which takes precedence over the cell colors.
+pub struct Render<'a, T> {
+    grid: &'a Grid<T>,
+    path: Option<(&'a [Position], Color)>,
+    markers: Vec<(Position, char, Color)>,
+    colors: Option<CellColors<'a, T>>,
+    rulers: bool,
+    viewport: Option<(Position, Dimensions)>,
+    ansi: bool,
+}
+
+impl<T> Grid<T> {
+    pub fn render(&self) -> Render<'_, T> {
+        Render {
+            grid: self,
+            path: None,
+            markers: Vec::new(),
+            colors: None,
+            rulers: false,
+            viewport: None,
+            ansi: true,
+        }
+    }
+}
+
+impl<'a, T> Render<'a, T> {
+    /// Highlights the background of the cells on the path.
+    pub fn path(mut self, path: &'a [Position], color: Color) -> Self {
+        self.path = Some((path, color));
+        self
+    }
+
+    /// Replaces a cell with the given character.
+    pub fn marker(mut self, pos: Position, character: char, color: Color) -> Self {
+        self.markers.push((pos, character, color));
+        self
+    }
+
+    /// Foreground color per cell, `None` keeps the default color.
+    pub fn colors(mut self, f: impl Fn(&Position, &T) -> Option<Color> + 'a) -> Self {
+        self.colors = Some(Box::new(f));
+        self
+    }
+
+    /// Adds column numbers above and row numbers to the left of the grid.
+    pub fn rulers(mut self) -> Self {
+        self.rulers = true;
+        self
+    }
+
+    /// Only renders the given part of the grid, clamped to the grid.
+    pub fn viewport(mut self, origin: Position, dimensions: Dimensions) -> Self {
+        self.viewport = Some((origin, dimensions));
+        self
+    }
+
+    /// Leaves out the escape sequences, for writing to files or comparing in tests.
+    pub fn plain(mut self) -> Self {
+        self.ansi = false;
+        self
+    }
+
+    fn bounds(&self) -> (Position, Position) {
+        let dimensions = self.grid.dimensions;
+        let (origin, size) = self.viewport.unwrap_or((Position(0, 0), dimensions));
+        let start = Position(origin.0.min(dimensions.0), origin.1.min(dimensions.1));
+        let end = Position(
+            (origin.0 + size.0).min(dimensions.0),
+            (origin.1 + size.1).min(dimensions.1),
+        );
+        (start, end)
+    }
+}
+
+impl<T> Display for Render<'_, T>
+where
+    T: CellDisplay,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (start, end) = self.bounds();
+        let label_width = end.0.saturating_sub(1).to_string().len();
+
+        if self.rulers {
+            let digits = end.1.saturating_sub(1).to_string().len();
+            for place in (0..digits as u32).rev() {
+                write!(f, "{:width$} ", "", width = label_width)?;
+                for x in start.1..end.1 {
+                    if x < 10usize.pow(place) && place > 0 {
+                        f.write_char(' ')?;
+                    } else {
+                        write!(f, "{}", x / 10usize.pow(place) % 10)?;
+                    }
+                }
+                f.write_char('\n')?;
+            }
+        }
+
+        let path = self
+            .path
+            .map(|(path, color)| (path.iter().collect::<FxHashSet<_>>(), color));
+        for y in start.0..end.0 {
+            if self.rulers {
+                write!(f, "{:>width$} ", y, width = label_width)?;
+            }
+            for x in start.1..end.1 {
+                let pos = Position(y, x);
+                let value = self.grid.get(&pos);
+                let marker = self.markers.iter().rev().find(|marker| marker.0 == pos);
+                let background = path
+                    .as_ref()
+                    .filter(|(path, _)| path.contains(&pos))
+                    .map(|(_, color)| *color);
+                let foreground = match marker {
+                    Some(&(_, _, color)) => Some(color),
+                    None => self.colors.as_ref().and_then(|colors| colors(&pos, value)),
+                };
+
+                let escaped = self.ansi && (foreground.is_some() || background.is_some());
+                if self.ansi {
+                    if let Some(color) = foreground {
+                        color.write_escape(f, false)?;
+                    }
+                    if let Some(color) = background {
+                        color.write_escape(f, true)?;
+                    }
+                }
+                match marker {
+                    Some(&(_, character, _)) => f.write_char(character)?,
+                    None => CellDisplay::fmt(value, f)?,
+                }
+                if escaped {
+                    f.write_str("\x1b[0m")?;
+                }
+            }
+            f.write_char('\n')?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn plain() {
+        let grid = Grid::from_rows(["#..........#", "#.........##"].map(|row| row.bytes()));
+        assert_eq!(
+            grid.render()
+                .marker(Position(1, 1), 'S', Color::Green)
+                .rulers()
+                .plain()
+                .to_string(),
+            "            11\n  012345678901\n0 #..........#\n1 #S........##\n"
+        );
+        assert_eq!(
+            grid.render()
+                .viewport(Position(1, 9), Dimensions(5, 5))
+                .rulers()
+                .plain()
+                .to_string(),
+            "   11\n  901\n1 .##\n"
+        );
+    }
+
+    #[test]
+    fn escapes() {
+        let grid = Grid::from_rows(["ab"].map(|row| row.bytes()));
+        assert_eq!(
+            grid.render()
+                .path(&[Position(0, 0)], Color::Blue)
+                .colors(|_, value| (*value == b'b').then_some(Color::Rgb(1, 2, 3)))
+                .to_string(),
+            "\x1b[44ma\x1b[0m\x1b[38;2;1;2;3mb\x1b[0m\n"
+        );
+    }
+}
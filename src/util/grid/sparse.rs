@@ -0,0 +1,253 @@
+use crate::util::grid::{CellDisplay, Grid};
+use crate::util::hash::FxHashMap;
+use crate::util::position::{
+    DIRECTIONS, DIRECTIONS8, Dimensions, Direction, Direction8, Point, Position,
+};
+use std::fmt::{Display, Formatter, Write};
+
+/// Grid on an unbounded plane, storing only the occupied cells. The bounding box of all cells
+/// that were inserted is tracked along the way.
+#[derive(Debug, Clone)]
+pub struct SparseGrid<T> {
+    cells: FxHashMap<Point, T>,
+    bounds: Option<(Point, Point)>,
+}
+
+impl<T> Default for SparseGrid<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> SparseGrid<T> {
+    pub fn new() -> Self {
+        Self {
+            cells: FxHashMap::default(),
+            bounds: None,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    pub fn contains(&self, point: &Point) -> bool {
+        self.cells.contains_key(point)
+    }
+    pub fn get(&self, point: &Point) -> Option<&T> {
+        self.cells.get(point)
+    }
+    pub fn get_mut(&mut self, point: &Point) -> Option<&mut T> {
+        self.cells.get_mut(point)
+    }
+
+    /// Inserts a value, returning the previous value at that point.
+    pub fn insert(&mut self, point: Point, value: T) -> Option<T> {
+        self.bounds = Some(match self.bounds {
+            None => (point, point),
+            Some((min, max)) => (
+                Point(min.0.min(point.0), min.1.min(point.1)),
+                Point(max.0.max(point.0), max.1.max(point.1)),
+            ),
+        });
+        self.cells.insert(point, value)
+    }
+
+    /// Removes a value. Removing a point on the edge of the bounding box recomputes it, which
+    /// takes time linear in the number of cells.
+    pub fn remove(&mut self, point: &Point) -> Option<T> {
+        let value = self.cells.remove(point)?;
+        if let Some((min, max)) = self.bounds
+            && (point.0 == min.0 || point.0 == max.0 || point.1 == min.1 || point.1 == max.1)
+        {
+            self.bounds = self.cells.keys().fold(None, |bounds, point| {
+                Some(match bounds {
+                    None => (*point, *point),
+                    Some((min, max)) => (
+                        Point(min.0.min(point.0), min.1.min(point.1)),
+                        Point(max.0.max(point.0), max.1.max(point.1)),
+                    ),
+                })
+            });
+        }
+        Some(value)
+    }
+
+    pub fn clear(&mut self) {
+        self.cells.clear();
+        self.bounds = None;
+    }
+
+    /// Iterates the occupied cells in arbitrary order.
+    pub fn iter(&self) -> impl Iterator<Item = (Point, &T)> {
+        self.cells.iter().map(|(point, value)| (*point, value))
+    }
+    pub fn points(&self) -> impl Iterator<Item = Point> + '_ {
+        self.cells.keys().copied()
+    }
+
+    /// Smallest and largest point of the bounding box, both inclusive.
+    pub fn bounds(&self) -> Option<(Point, Point)> {
+        self.bounds
+    }
+
+    /// Dimensions of the bounding box, zero when the grid is empty.
+    pub fn dimensions(&self) -> Dimensions {
+        match self.bounds {
+            None => Dimensions(0, 0),
+            Some((min, max)) => Dimensions(max.0.abs_diff(min.0) + 1, max.1.abs_diff(min.1) + 1),
+        }
+    }
+
+    pub fn neighbors4(&self, point: &Point) -> impl Iterator<Item = (Direction, Point, &T)> {
+        let point = *point;
+        DIRECTIONS.into_iter().filter_map(move |direction| {
+            let neighbor = point.moved(&direction);
+            Some((direction, neighbor, self.cells.get(&neighbor)?))
+        })
+    }
+
+    pub fn neighbors8(&self, point: &Point) -> impl Iterator<Item = (Direction8, Point, &T)> {
+        let point = *point;
+        DIRECTIONS8.into_iter().filter_map(move |direction| {
+            let neighbor = point.offset(&(&direction).into());
+            Some((direction, neighbor, self.cells.get(&neighbor)?))
+        })
+    }
+
+    /// Sparse copy of the cells of a dense grid for which `f` returns true.
+    pub fn from_grid_where(grid: &Grid<T>, mut f: impl FnMut(&T) -> bool) -> Self
+    where
+        T: Clone,
+    {
+        grid.iter()
+            .filter(|(_, value)| f(value))
+            .map(|(pos, value)| (pos.into(), value.clone()))
+            .collect()
+    }
+
+    /// Dense copy of the bounding box, with `empty` in the unoccupied cells. Also returns the point
+    /// that ends up at `Position(0, 0)`.
+    pub fn to_grid(&self, empty: T) -> (Grid<T>, Point)
+    where
+        T: Clone,
+    {
+        let origin = self.bounds.map_or(Point(0, 0), |(min, _)| min);
+        let mut grid = Grid::from_dimensions(self.dimensions(), empty);
+        self.iter().for_each(|(point, value)| {
+            let pos = Position((point.0 - origin.0) as usize, (point.1 - origin.1) as usize);
+            grid.set(&pos, value.clone());
+        });
+        (grid, origin)
+    }
+}
+
+impl<T> From<Grid<T>> for SparseGrid<T> {
+    fn from(grid: Grid<T>) -> Self {
+        let width = grid.dimensions.1;
+        grid.data
+            .into_iter()
+            .enumerate()
+            .map(|(i, value)| (Point((i / width) as isize, (i % width) as isize), value))
+            .collect()
+    }
+}
+
+impl<T> FromIterator<(Point, T)> for SparseGrid<T> {
+    fn from_iter<I: IntoIterator<Item = (Point, T)>>(iter: I) -> Self {
+        let mut grid = Self::new();
+        grid.extend(iter);
+        grid
+    }
+}
+
+impl<T> Extend<(Point, T)> for SparseGrid<T> {
+    fn extend<I: IntoIterator<Item = (Point, T)>>(&mut self, iter: I) {
+        iter.into_iter().for_each(|(point, value)| {
+            self.insert(point, value);
+        });
+    }
+}
+
+/// Renders the bounding box, unoccupied cells are shown as spaces.
+impl<T> Display for SparseGrid<T>
+where
+    T: CellDisplay,
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let Some((min, max)) = self.bounds else {
+            return Ok(());
+        };
+        (min.0..=max.0).try_for_each(|y| {
+            (min.1..=max.1).try_for_each(|x| match self.cells.get(&Point(y, x)) {
+                Some(value) => CellDisplay::fmt(value, f),
+                None => f.write_char(' '),
+            })?;
+            f.write_char('\n')
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn bounds() {
+        let mut grid = SparseGrid::new();
+        assert_eq!(grid.dimensions(), Dimensions(0, 0));
+        grid.insert(Point(-2, 3), true);
+        grid.insert(Point(1, -1), true);
+        grid.insert(Point(0, 0), false);
+        assert_eq!(grid.bounds(), Some((Point(-2, -1), Point(1, 3))));
+        assert_eq!(grid.dimensions(), Dimensions(4, 5));
+
+        grid.remove(&Point(0, 0));
+        assert_eq!(grid.bounds(), Some((Point(-2, -1), Point(1, 3))));
+        grid.remove(&Point(-2, 3));
+        assert_eq!(grid.bounds(), Some((Point(1, -1), Point(1, -1))));
+        grid.remove(&Point(1, -1));
+        assert_eq!(grid.bounds(), None);
+    }
+
+    #[test]
+    fn neighbors() {
+        let grid: SparseGrid<_> = [(Point(-1, 0), 1), (Point(0, 1), 2), (Point(1, 1), 3)]
+            .into_iter()
+            .collect();
+        assert_eq!(
+            grid.neighbors4(&Point(0, 0)).collect::<Vec<_>>(),
+            [
+                (Direction::Up, Point(-1, 0), &1),
+                (Direction::Right, Point(0, 1), &2)
+            ]
+        );
+        assert_eq!(grid.neighbors8(&Point(0, 0)).count(), 3);
+    }
+
+    #[test]
+    fn negative_points_to_grid() {
+        let sparse: SparseGrid<u8> = [
+            (Point(-2, -3), b'a'),
+            (Point(0, -1), b'b'),
+            (Point(-1, 0), b'c'),
+        ]
+        .into_iter()
+        .collect();
+        assert_eq!(sparse.to_string(), "a   \n   c\n  b \n");
+
+        let (grid, origin) = sparse.to_grid(b'.');
+        assert_eq!(origin, Point(-2, -3));
+        assert_eq!(grid.to_string(), "a...\n...c\n..b.\n");
+
+        let shifted = SparseGrid::from_grid_where(&grid, |value| *value != b'.');
+        assert_eq!(shifted.bounds(), Some((Point(0, 0), Point(2, 3))));
+        assert!(sparse.iter().all(|(point, value)| {
+            shifted.get(&Point(point.0 - origin.0, point.1 - origin.1)) == Some(value)
+        }));
+        assert_eq!(SparseGrid::from(grid).len(), 12);
+    }
+}
@@ -0,0 +1,208 @@
+use crate::util::grid::{BitGrid, Grid};
+use crate::util::position::{
+    DIRECTIONS, DIRECTIONS8, Dimensions, Direction, Direction8, Point, Position, PositionOffset,
+};
+
+/// Storage that can be wrapped into a `Torus`.
+pub trait TorusStorage {
+    fn dimensions(&self) -> Dimensions;
+}
+
+impl<T> TorusStorage for Grid<T> {
+    fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+}
+
+impl TorusStorage for BitGrid {
+    fn dimensions(&self) -> Dimensions {
+        self.dimensions
+    }
+}
+
+/// Grid where leaving one side enters on the opposite side. Offsets, neighbors and walks wrap
+/// around, so every position has all of its neighbors. The cells are stored in a `Grid`, or in a
+/// `BitGrid` when only occupancy is needed.
+#[derive(Debug, Clone)]
+pub struct Torus<G> {
+    pub grid: G,
+}
+
+impl<G: TorusStorage> Torus<G> {
+    pub fn new(grid: G) -> Self {
+        Self { grid }
+    }
+
+    pub fn dimensions(&self) -> Dimensions {
+        self.grid.dimensions()
+    }
+
+    /// Position of a point on the unbounded plane that is repeated by the torus.
+    pub fn wrap(&self, point: &Point) -> Position {
+        let dimensions = self.dimensions();
+        Position(
+            point.0.rem_euclid(dimensions.0 as isize) as usize,
+            point.1.rem_euclid(dimensions.1 as isize) as usize,
+        )
+    }
+
+    pub fn offset(&self, pos: &Position, offset: &PositionOffset) -> Position {
+        self.offset_steps(pos, offset, 1)
+    }
+
+    pub fn moved(&self, pos: &Position, direction: &Direction) -> Position {
+        self.offset_steps(pos, &direction.into(), 1)
+    }
+
+    /// Velocity as the number of forward steps along each axis, for `offset_steps_reduced`.
+    pub fn reduce_velocity(&self, velocity: &PositionOffset) -> Position {
+        self.wrap(&Point(velocity.0, velocity.1))
+    }
+
+    /// Number of steps modulo the height and the width, for `offset_steps_reduced`.
+    pub fn reduce_steps(&self, steps: usize) -> Position {
+        let dimensions = self.dimensions();
+        Position(steps % dimensions.0, steps % dimensions.1)
+    }
+
+    /// Position after moving `steps` times by `velocity`. The velocity and the number of steps
+    /// are reduced modulo the dimensions first, so large step counts cannot overflow.
+    pub fn offset_steps(
+        &self,
+        pos: &Position,
+        velocity: &PositionOffset,
+        steps: usize,
+    ) -> Position {
+        self.offset_steps_reduced(
+            pos,
+            &self.reduce_velocity(velocity),
+            &self.reduce_steps(steps),
+        )
+    }
+
+    /// Same as `offset_steps` with the velocity and steps reduced already, so moving many
+    /// positions by the same number of steps only costs a remainder per axis.
+    pub fn offset_steps_reduced(
+        &self,
+        pos: &Position,
+        velocity: &Position,
+        steps: &Position,
+    ) -> Position {
+        let dimensions = self.dimensions();
+        Position(
+            (pos.0 + velocity.0 * steps.0) % dimensions.0,
+            (pos.1 + velocity.1 * steps.1) % dimensions.1,
+        )
+    }
+
+    /// Endless walk from `pos`, starting with the first step.
+    pub fn walk(
+        &self,
+        pos: &Position,
+        offset: &PositionOffset,
+    ) -> impl Iterator<Item = Position> + '_ {
+        let offset = *offset;
+        std::iter::successors(Some(*pos), move |pos| Some(self.offset(pos, &offset))).skip(1)
+    }
+}
+
+impl<T> Torus<Grid<T>> {
+    pub fn from_dimensions(dimensions: Dimensions, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self::new(Grid::from_dimensions(dimensions, value))
+    }
+
+    pub fn get(&self, pos: &Position) -> &T {
+        self.grid.get(pos)
+    }
+    pub fn get_mut(&mut self, pos: &Position) -> &mut T {
+        self.grid.get_mut(pos)
+    }
+    pub fn set(&mut self, pos: &Position, value: T) {
+        self.grid.set(pos, value);
+    }
+    pub fn get_wrapped(&self, point: &Point) -> &T {
+        self.grid.get(&self.wrap(point))
+    }
+
+    pub fn iter(&self) -> impl DoubleEndedIterator<Item = (Position, &T)> + '_ {
+        self.grid.iter()
+    }
+
+    pub fn neighbors4(&self, pos: &Position) -> impl Iterator<Item = (Direction, Position, &T)> {
+        let pos = *pos;
+        DIRECTIONS.into_iter().map(move |direction| {
+            let next = self.moved(&pos, &direction);
+            (direction, next, self.get(&next))
+        })
+    }
+
+    pub fn neighbors8(&self, pos: &Position) -> impl Iterator<Item = (Direction8, Position, &T)> {
+        let pos = *pos;
+        DIRECTIONS8.into_iter().map(move |direction| {
+            let next = self.offset(&pos, &(&direction).into());
+            (direction, next, self.get(&next))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn wrapping() {
+        let torus = Torus::from_dimensions(Dimensions(7, 11), 0);
+        let velocity = PositionOffset(-3, 2);
+        let stepped = (0..100).fold(Position(4, 2), |pos, _| torus.offset(&pos, &velocity));
+        assert_eq!(torus.offset_steps(&Position(4, 2), &velocity, 100), stepped);
+        assert_eq!(
+            torus.offset_steps(&Position(4, 2), &velocity, usize::MAX),
+            torus.wrap(&Point(
+                4 - 3 * (usize::MAX % 7) as isize,
+                2 + 2 * (usize::MAX % 11) as isize
+            ))
+        );
+        assert_eq!(torus.wrap(&Point(-1, -12)), Position(6, 10));
+        assert_eq!(
+            torus
+                .walk(&Position(0, 9), &PositionOffset::right())
+                .take(3)
+                .collect::<Vec<_>>(),
+            [Position(0, 10), Position(0, 0), Position(0, 1)]
+        );
+        assert_eq!(
+            Position(4, 2).wrapping_offset(&torus.dimensions(), &(velocity * 100)),
+            stepped
+        );
+    }
+
+    #[test]
+    fn neighbors() {
+        let mut torus = Torus::from_dimensions(Dimensions(3, 3), 0);
+        torus.set(&Position(2, 2), 1);
+        assert_eq!(torus.neighbors4(&Position(0, 0)).count(), 4);
+        assert_eq!(
+            torus
+                .neighbors8(&Position(0, 0))
+                .filter(|(_, _, value)| **value == 1)
+                .map(|(direction, _, _)| direction)
+                .collect::<Vec<_>>(),
+            [Direction8::UpLeft]
+        );
+        assert_eq!(*torus.get_wrapped(&Point(-1, 5)), 1);
+    }
+
+    #[test]
+    fn bit_storage() {
+        let mut torus = Torus::new(BitGrid::new(Dimensions(3, 5)));
+        let velocity = torus.reduce_velocity(&PositionOffset(-1, 7));
+        assert_eq!(velocity, Position(2, 2));
+        let pos = torus.offset_steps_reduced(&Position(0, 0), &velocity, &torus.reduce_steps(4));
+        assert_eq!(pos, Position(2, 3));
+        assert!(torus.grid.insert(&pos));
+        assert!(torus.grid.contains(&Position(2, 3)));
+    }
+}
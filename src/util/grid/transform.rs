@@ -0,0 +1,297 @@
+use crate::util::grid::Grid;
+use crate::util::position::{Dimensions, Position};
+
+/// Rotations and flips of a grid. Flipping horizontally mirrors the columns, flipping vertically
+/// mirrors the rows.
+#[derive(Debug, Copy, Clone, Eq, PartialEq)]
+pub enum Transform {
+    Identity,
+    RotateClockwise,
+    Rotate180,
+    RotateAnticlockwise,
+    FlipHorizontal,
+    FlipVertical,
+    Transpose,
+}
+
+impl Transform {
+    fn swaps_axes(&self) -> bool {
+        matches!(
+            self,
+            Transform::RotateClockwise | Transform::RotateAnticlockwise | Transform::Transpose
+        )
+    }
+
+    /// Dimensions after applying the transform to a grid of the given dimensions.
+    pub fn dimensions(&self, dimensions: Dimensions) -> Dimensions {
+        if self.swaps_axes() {
+            Dimensions(dimensions.1, dimensions.0)
+        } else {
+            dimensions
+        }
+    }
+
+    /// Position in the source grid for a position in the transformed grid.
+    fn source(&self, source_dimensions: &Dimensions, pos: &Position) -> Position {
+        let Dimensions(height, width) = *source_dimensions;
+        match self {
+            Transform::Identity => *pos,
+            Transform::RotateClockwise => Position(height - 1 - pos.1, pos.0),
+            Transform::Rotate180 => Position(height - 1 - pos.0, width - 1 - pos.1),
+            Transform::RotateAnticlockwise => Position(pos.1, width - 1 - pos.0),
+            Transform::FlipHorizontal => Position(pos.0, width - 1 - pos.1),
+            Transform::FlipVertical => Position(height - 1 - pos.0, pos.1),
+            Transform::Transpose => Position(pos.1, pos.0),
+        }
+    }
+}
+
+/// Borrowed view of a grid with a transform applied, without copying.
+pub struct TransformedGrid<'grid, T> {
+    grid: &'grid Grid<T>,
+    transform: Transform,
+    pub dimensions: Dimensions,
+}
+
+impl<'grid, T> TransformedGrid<'grid, T> {
+    pub fn get(&self, pos: &Position) -> &'grid T {
+        assert!(pos.0 < self.dimensions.0 && pos.1 < self.dimensions.1);
+        self.grid
+            .get(&self.transform.source(&self.grid.dimensions, pos))
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &'grid T)> + '_ {
+        let dimensions = self.dimensions;
+        (0..dimensions.0).flat_map(move |y| {
+            (0..dimensions.1).map(move |x| {
+                let pos = Position(y, x);
+                (pos, self.get(&pos))
+            })
+        })
+    }
+
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            dimensions: self.dimensions,
+            data: self.iter().map(|(_, value)| value.clone()).collect(),
+        }
+    }
+}
+
+/// Borrowed rectangular part of a grid, rows are available as slices.
+pub struct SubGrid<'grid, T> {
+    grid: &'grid Grid<T>,
+    origin: Position,
+    pub dimensions: Dimensions,
+}
+
+impl<'grid, T> SubGrid<'grid, T> {
+    pub fn get(&self, pos: &Position) -> &'grid T {
+        assert!(pos.0 < self.dimensions.0 && pos.1 < self.dimensions.1);
+        self.grid
+            .get(&Position(self.origin.0 + pos.0, self.origin.1 + pos.1))
+    }
+
+    pub fn get_row(&self, j: usize) -> &'grid [T] {
+        assert!(j < self.dimensions.0);
+        &self.grid.get_row(self.origin.0 + j)[self.origin.1..self.origin.1 + self.dimensions.1]
+    }
+
+    pub fn rows(&self) -> impl Iterator<Item = &'grid [T]> + '_ {
+        (0..self.dimensions.0).map(|j| self.get_row(j))
+    }
+
+    /// Iterates the cells, with positions relative to the origin of the subgrid.
+    pub fn iter(&self) -> impl Iterator<Item = (Position, &'grid T)> + '_ {
+        self.rows().enumerate().flat_map(|(y, row)| {
+            row.iter()
+                .enumerate()
+                .map(move |(x, value)| (Position(y, x), value))
+        })
+    }
+
+    pub fn to_grid(&self) -> Grid<T>
+    where
+        T: Clone,
+    {
+        Grid {
+            dimensions: self.dimensions,
+            data: self.rows().flatten().cloned().collect(),
+        }
+    }
+}
+
+impl<T> Grid<T> {
+    pub fn view(&self, transform: Transform) -> TransformedGrid<'_, T> {
+        TransformedGrid {
+            grid: self,
+            transform,
+            dimensions: transform.dimensions(self.dimensions),
+        }
+    }
+
+    pub fn transformed(&self, transform: Transform) -> Self
+    where
+        T: Clone,
+    {
+        self.view(transform).to_grid()
+    }
+
+    pub fn subgrid(&self, origin: Position, dimensions: Dimensions) -> SubGrid<'_, T> {
+        assert!(
+            origin.0 + dimensions.0 <= self.dimensions.0
+                && origin.1 + dimensions.1 <= self.dimensions.1
+        );
+        SubGrid {
+            grid: self,
+            origin,
+            dimensions,
+        }
+    }
+
+    /// Copy with a border of `width` cells around it.
+    pub fn padded(&self, width: usize, value: T) -> Self
+    where
+        T: Clone,
+    {
+        let dimensions = Dimensions(self.dimensions.0 + 2 * width, self.dimensions.1 + 2 * width);
+        let mut data = Vec::with_capacity(dimensions.0 * dimensions.1);
+        data.resize(width * dimensions.1, value.clone());
+        for row in self.rows() {
+            data.extend(std::iter::repeat_n(value.clone(), width));
+            data.extend_from_slice(row);
+            data.extend(std::iter::repeat_n(value.clone(), width));
+        }
+        data.resize(dimensions.0 * dimensions.1, value);
+        Self { dimensions, data }
+    }
+
+    /// Copy of the grid repeated `vertical` times downwards and `horizontal` times to the right.
+    pub fn tiled(&self, vertical: usize, horizontal: usize) -> Self
+    where
+        T: Clone,
+    {
+        Grid {
+            dimensions: Dimensions(self.dimensions.0 * vertical, self.dimensions.1 * horizontal),
+            data: (0..vertical)
+                .flat_map(|_| self.rows())
+                .flat_map(|row| (0..horizontal).flat_map(move |_| row.iter().cloned()))
+                .collect(),
+        }
+    }
+
+    /// Replaces every cell by `N` cells next to each other, such as a doubled width map.
+    pub fn widened<U, const N: usize>(&self, mut f: impl FnMut(&T) -> [U; N]) -> Grid<U> {
+        Grid {
+            dimensions: Dimensions(self.dimensions.0, self.dimensions.1 * N),
+            data: self.data.iter().flat_map(&mut f).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn grid() -> Grid<u8> {
+        Grid::from_rows([[1, 2, 3], [4, 5, 6]])
+    }
+
+    #[test]
+    fn transforms() {
+        let grid = grid();
+        assert_eq!(
+            grid.transformed(Transform::RotateClockwise),
+            Grid::from_rows([[4, 1], [5, 2], [6, 3]])
+        );
+        assert_eq!(
+            grid.transformed(Transform::RotateAnticlockwise),
+            Grid::from_rows([[3, 6], [2, 5], [1, 4]])
+        );
+        assert_eq!(
+            grid.transformed(Transform::Rotate180),
+            Grid::from_rows([[6, 5, 4], [3, 2, 1]])
+        );
+        assert_eq!(
+            grid.transformed(Transform::FlipHorizontal),
+            Grid::from_rows([[3, 2, 1], [6, 5, 4]])
+        );
+        assert_eq!(
+            grid.transformed(Transform::FlipVertical),
+            Grid::from_rows([[4, 5, 6], [1, 2, 3]])
+        );
+        assert_eq!(grid.transformed(Transform::Transpose), grid.transposed());
+        assert_eq!(grid.transformed(Transform::Identity), grid);
+    }
+    #[test]
+    fn subgrid() {
+        let grid = grid();
+        let subgrid = grid.subgrid(Position(0, 1), Dimensions(2, 2));
+        assert_eq!(subgrid.get_row(1), [5, 6]);
+        assert_eq!(*subgrid.get(&Position(0, 0)), 2);
+        assert_eq!(subgrid.to_grid(), Grid::from_rows([[2, 3], [5, 6]]));
+    }
+    #[test]
+    fn empty_results() {
+        let grid = grid();
+        assert_eq!(grid.tiled(0, 2).dimensions, Dimensions(0, 6));
+        assert_eq!(grid.tiled(2, 0).dimensions, Dimensions(4, 0));
+        let subgrid = grid.subgrid(Position(1, 1), Dimensions(0, 2));
+        assert_eq!(subgrid.to_grid().dimensions, Dimensions(0, 2));
+        let subgrid = grid.subgrid(Position(1, 1), Dimensions(1, 0));
+        assert_eq!(subgrid.to_grid().dimensions, Dimensions(1, 0));
+        let empty = grid.tiled(0, 1);
+        assert_eq!(
+            empty.view(Transform::RotateClockwise).to_grid().dimensions,
+            Dimensions(3, 0)
+        );
+    }
+    #[test]
+    fn empty_results_rows() {
+        let narrow = Grid::from_rows([*b"ab", *b"cd"]).tiled(2, 0);
+        assert_eq!(narrow.rows().count(), 0);
+        assert_eq!(narrow.to_text(&Default::default()), "");
+        assert_eq!(narrow.to_string(), "");
+        assert_eq!(
+            narrow.padded(1, b'.'),
+            Grid::from_dimensions(Dimensions(6, 2), b'.')
+        );
+        let flat = narrow.transformed(Transform::Transpose);
+        assert_eq!(flat.dimensions, Dimensions(0, 4));
+        assert_eq!(flat.rows().count(), 0);
+        assert_eq!(flat.to_text(&Default::default()), "");
+        assert_eq!(
+            flat.padded(1, b'.'),
+            Grid::from_dimensions(Dimensions(2, 6), b'.')
+        );
+    }
+    #[test]
+    fn padded_tiled_widened() {
+        let grid = grid();
+        assert_eq!(
+            grid.padded(1, 0),
+            Grid::from_rows([
+                [0, 0, 0, 0, 0],
+                [0, 1, 2, 3, 0],
+                [0, 4, 5, 6, 0],
+                [0, 0, 0, 0, 0]
+            ])
+        );
+        assert_eq!(
+            grid.tiled(2, 2),
+            Grid::from_rows([
+                [1, 2, 3, 1, 2, 3],
+                [4, 5, 6, 4, 5, 6],
+                [1, 2, 3, 1, 2, 3],
+                [4, 5, 6, 4, 5, 6]
+            ])
+        );
+        assert_eq!(
+            grid.widened(|v| [*v, *v * 10]),
+            Grid::from_rows([[1, 10, 2, 20, 3, 30], [4, 40, 5, 50, 6, 60]])
+        );
+    }
+}
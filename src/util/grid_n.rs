@@ -0,0 +1,225 @@
+//! Positions and grids with any number of axes, for voxel and higher-dimensional cellular
+//! automaton puzzles. Axes are ordered like the 2D types, so the last axis varies fastest.
+
+use std::ops::{Index, IndexMut};
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash)]
+pub struct DimensionsN<const D: usize>(pub [usize; D]);
+
+impl<const D: usize> DimensionsN<D> {
+    /// Number of cells, the exclusive upper bound of `PositionN::pack`.
+    pub fn size(&self) -> usize {
+        self.0.iter().product()
+    }
+
+    pub fn contains(&self, pos: &PositionN<D>) -> bool {
+        pos.0.iter().zip(self.0).all(|(value, size)| *value < size)
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PositionOffsetN<const D: usize>(pub [isize; D]);
+
+impl<const D: usize> PositionOffsetN<D> {
+    /// Unit offsets along every axis, two per axis.
+    pub fn orthogonal() -> impl Iterator<Item = Self> + Clone {
+        (0..D).flat_map(|axis| {
+            [-1, 1].map(|step| {
+                let mut offset = [0; D];
+                offset[axis] = step;
+                Self(offset)
+            })
+        })
+    }
+
+    /// Offsets to every cell of the surrounding cube, `3^D - 1` in total.
+    pub fn all() -> impl Iterator<Item = Self> + Clone {
+        (0..3usize.pow(D as u32))
+            .map(|mut index| {
+                Self(std::array::from_fn(|_| {
+                    let step = (index % 3) as isize - 1;
+                    index /= 3;
+                    step
+                }))
+            })
+            .filter(|offset| offset.0 != [0; D])
+    }
+}
+
+#[derive(Copy, Clone, Debug, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct PositionN<const D: usize>(pub [usize; D]);
+
+impl<const D: usize> PositionN<D> {
+    pub fn checked_offset(
+        &self,
+        dimensions: &DimensionsN<D>,
+        offset: &PositionOffsetN<D>,
+    ) -> Option<Self> {
+        let mut result = [0; D];
+        for (axis, value) in result.iter_mut().enumerate() {
+            *value = self.0[axis]
+                .checked_add_signed(offset.0[axis])
+                .filter(|value| *value < dimensions.0[axis])?;
+        }
+        Some(Self(result))
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> usize {
+        self.0.iter().zip(other.0).map(|(a, b)| a.abs_diff(b)).sum()
+    }
+
+    /// Neighbors along the axes that are within the dimensions.
+    pub fn neighbors(&self, dimensions: &DimensionsN<D>) -> impl Iterator<Item = Self> {
+        let (pos, dimensions) = (*self, *dimensions);
+        PositionOffsetN::orthogonal()
+            .filter_map(move |offset| pos.checked_offset(&dimensions, &offset))
+    }
+
+    /// Neighbors in the surrounding cube that are within the dimensions.
+    pub fn neighbors_all(&self, dimensions: &DimensionsN<D>) -> impl Iterator<Item = Self> {
+        let (pos, dimensions) = (*self, *dimensions);
+        PositionOffsetN::all().filter_map(move |offset| pos.checked_offset(&dimensions, &offset))
+    }
+
+    /// Dense index within the dimensions, with the last axis varying fastest.
+    pub fn pack(&self, dimensions: &DimensionsN<D>) -> usize {
+        debug_assert!(dimensions.contains(self));
+        self.0
+            .iter()
+            .zip(dimensions.0)
+            .fold(0, |index, (value, size)| index * size + value)
+    }
+
+    pub fn unpack(mut index: usize, dimensions: &DimensionsN<D>) -> Self {
+        let mut pos = [0; D];
+        for axis in (0..D).rev() {
+            pos[axis] = index % dimensions.0[axis];
+            index /= dimensions.0[axis];
+        }
+        Self(pos)
+    }
+}
+
+pub type Position3 = PositionN<3>;
+pub type Position4 = PositionN<4>;
+
+/// Dense grid with `D` axes, the counterpart of `Grid` for voxels and hypercubes.
+#[derive(Clone, Eq, PartialEq, Hash, Debug)]
+pub struct GridN<T, const D: usize> {
+    pub dimensions: DimensionsN<D>,
+    data: Vec<T>,
+}
+
+pub type Grid3<T> = GridN<T, 3>;
+pub type Grid4<T> = GridN<T, 4>;
+
+impl<T, const D: usize> GridN<T, D> {
+    pub fn from_dimensions(dimensions: DimensionsN<D>, value: T) -> Self
+    where
+        T: Clone,
+    {
+        Self {
+            dimensions,
+            data: vec![value; dimensions.size()],
+        }
+    }
+
+    pub fn get(&self, pos: &PositionN<D>) -> &T {
+        &self.data[pos.pack(&self.dimensions)]
+    }
+
+    pub fn get_checked(&self, pos: &PositionN<D>) -> Option<&T> {
+        self.dimensions.contains(pos).then(|| self.get(pos))
+    }
+
+    pub fn get_mut(&mut self, pos: &PositionN<D>) -> &mut T {
+        let index = pos.pack(&self.dimensions);
+        &mut self.data[index]
+    }
+
+    pub fn set(&mut self, pos: &PositionN<D>, value: T) {
+        *self.get_mut(pos) = value;
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (PositionN<D>, &T)> {
+        self.data
+            .iter()
+            .enumerate()
+            .map(|(index, value)| (PositionN::unpack(index, &self.dimensions), value))
+    }
+
+    pub fn values(&self) -> impl Iterator<Item = &T> + '_ {
+        self.data.iter()
+    }
+
+    pub fn positions(&self) -> impl Iterator<Item = PositionN<D>> + use<T, D> {
+        let dimensions = self.dimensions;
+        (0..dimensions.size()).map(move |index| PositionN::unpack(index, &dimensions))
+    }
+
+    /// Neighbors along the axes that are within the grid.
+    pub fn neighbors(&self, pos: &PositionN<D>) -> impl Iterator<Item = (PositionN<D>, &T)> {
+        pos.neighbors(&self.dimensions)
+            .map(|next| (next, self.get(&next)))
+    }
+
+    /// Neighbors in the surrounding cube that are within the grid.
+    pub fn neighbors_all(&self, pos: &PositionN<D>) -> impl Iterator<Item = (PositionN<D>, &T)> {
+        pos.neighbors_all(&self.dimensions)
+            .map(|next| (next, self.get(&next)))
+    }
+}
+
+impl<T, const D: usize> Index<PositionN<D>> for GridN<T, D> {
+    type Output = T;
+
+    fn index(&self, index: PositionN<D>) -> &Self::Output {
+        self.get(&index)
+    }
+}
+
+impl<T, const D: usize> IndexMut<PositionN<D>> for GridN<T, D> {
+    fn index_mut(&mut self, index: PositionN<D>) -> &mut Self::Output {
+        self.get_mut(&index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn positions() {
+        let dimensions = DimensionsN([2, 3, 4]);
+        assert_eq!(dimensions.size(), 24);
+        assert!(!dimensions.contains(&PositionN([1, 3, 0])));
+        assert_eq!(PositionN([0, 0, 0]).neighbors(&dimensions).count(), 3);
+        assert_eq!(PositionN([1, 1, 1]).neighbors(&dimensions).count(), 5);
+        assert_eq!(PositionOffsetN::<4>::all().count(), 80);
+        assert_eq!(
+            PositionN([0, 2, 5, 1]).manhattan_distance(&PositionN([3, 0, 5, 2])),
+            6
+        );
+
+        let pos = PositionN([1, 2, 3]);
+        assert_eq!(pos.pack(&dimensions), 23);
+        assert_eq!(PositionN::unpack(23, &dimensions), pos);
+    }
+
+    #[test]
+    fn grid() {
+        let mut grid = Grid4::from_dimensions(DimensionsN([3, 3, 3, 3]), false);
+        grid.set(&PositionN([1, 1, 1, 1]), true);
+        grid[PositionN([0, 0, 0, 0])] = true;
+        assert_eq!(grid.values().filter(|value| **value).count(), 2);
+        assert_eq!(
+            grid.neighbors_all(&PositionN([1, 1, 1, 1]))
+                .filter(|(_, value)| **value)
+                .count(),
+            1
+        );
+        assert_eq!(grid.neighbors(&PositionN([0, 0, 0, 0])).count(), 4);
+        assert_eq!(grid.get_checked(&PositionN([0, 0, 3, 0])), None);
+        assert_eq!(grid.positions().last(), Some(PositionN([2, 2, 2, 2])));
+    }
+}
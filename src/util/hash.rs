@@ -0,0 +1,115 @@
+//! Drop-in replacements for the `rustc_hash` collections whose hasher can be seeded at runtime.
+//!
+//! With the default seed of zero these behave exactly like `FxHashMap` and `FxHashSet`. A different
+//! seed changes both the hashes and the iteration order, which is used to check that solutions do
+//! not depend on either.
+
+use rustc_hash::FxHasher;
+use std::hash::{BuildHasher, Hasher};
+use std::sync::atomic::{AtomicU64, Ordering};
+
+static SEED: AtomicU64 = AtomicU64::new(0);
+
+/// Sets the seed used by collections created afterwards. Zero restores the plain Fx hash.
+pub fn set_seed(seed: u64) {
+    SEED.store(seed, Ordering::Relaxed);
+}
+
+pub fn seed() -> u64 {
+    SEED.load(Ordering::Relaxed)
+}
+
+pub type FxHashMap<K, V> = std::collections::HashMap<K, V, FxBuildHasher>;
+pub type FxHashSet<V> = std::collections::HashSet<V, FxBuildHasher>;
+
+/// Captures the global seed on creation.
+#[derive(Copy, Clone, Debug)]
+pub struct FxBuildHasher {
+    seed: u64,
+}
+
+impl Default for FxBuildHasher {
+    fn default() -> Self {
+        Self { seed: seed() }
+    }
+}
+
+impl BuildHasher for FxBuildHasher {
+    type Hasher = SeededFxHasher;
+
+    #[inline]
+    fn build_hasher(&self) -> Self::Hasher {
+        SeededFxHasher {
+            inner: FxHasher::with_seed(self.seed as usize),
+            // Odd, so the multiplication permutes the hashes instead of only shifting them
+            multiplier: self.seed | 1,
+        }
+    }
+}
+
+pub struct SeededFxHasher {
+    inner: FxHasher,
+    multiplier: u64,
+}
+
+impl Hasher for SeededFxHasher {
+    #[inline]
+    fn finish(&self) -> u64 {
+        self.inner.finish().wrapping_mul(self.multiplier)
+    }
+
+    #[inline]
+    fn write(&mut self, bytes: &[u8]) {
+        self.inner.write(bytes);
+    }
+
+    #[inline]
+    fn write_u8(&mut self, i: u8) {
+        self.inner.write_u8(i);
+    }
+
+    #[inline]
+    fn write_u16(&mut self, i: u16) {
+        self.inner.write_u16(i);
+    }
+
+    #[inline]
+    fn write_u32(&mut self, i: u32) {
+        self.inner.write_u32(i);
+    }
+
+    #[inline]
+    fn write_u64(&mut self, i: u64) {
+        self.inner.write_u64(i);
+    }
+
+    #[inline]
+    fn write_u128(&mut self, i: u128) {
+        self.inner.write_u128(i);
+    }
+
+    #[inline]
+    fn write_usize(&mut self, i: usize) {
+        self.inner.write_usize(i);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unseeded_matches_fx() {
+        let seeded = FxBuildHasher { seed: 0 };
+        let fx = rustc_hash::FxBuildHasher;
+        for value in [0u64, 1, 42, u64::MAX] {
+            assert_eq!(seeded.hash_one(value), fx.hash_one(value));
+        }
+    }
+    #[test]
+    fn seeded_differs() {
+        let seeded = FxBuildHasher { seed: 0x1234_5678 };
+        let fx = rustc_hash::FxBuildHasher;
+        assert_ne!(seeded.hash_one(42u64), fx.hash_one(42u64));
+    }
+}
@@ -0,0 +1,174 @@
+//! Hexagonal grids with pointy-top cells in axial coordinates. A `Grid` stores such a board with
+//! rows as `r` and columns as `q`, so every row is shifted half a cell against the one above it.
+
+use crate::util::grid::Grid;
+use crate::util::position::{Dimensions, Position, PositionOffset, RotationalDirection};
+
+/// Direction to one of the six neighbors of a hexagon. The discriminants count clockwise from
+/// east in 60 degree steps.
+#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+pub enum HexDirection {
+    East = 0,
+    SouthEast = 1,
+    SouthWest = 2,
+    West = 3,
+    NorthWest = 4,
+    NorthEast = 5,
+}
+pub const HEX_DIRECTIONS: [HexDirection; 6] = [
+    HexDirection::East,
+    HexDirection::SouthEast,
+    HexDirection::SouthWest,
+    HexDirection::West,
+    HexDirection::NorthWest,
+    HexDirection::NorthEast,
+];
+
+impl HexDirection {
+    /// Neighbor direction by discriminant, `index` must be below 6.
+    pub fn from_index(index: usize) -> Self {
+        HEX_DIRECTIONS[index]
+    }
+
+    /// Turns by 60 degrees.
+    #[must_use]
+    pub fn rotated(self, rotational_direction: &RotationalDirection) -> Self {
+        let steps = match rotational_direction {
+            RotationalDirection::Clockwise => 1,
+            RotationalDirection::Anticlockwise => 5,
+        };
+        Self::from_index((self as usize + steps) % 6)
+    }
+
+    #[must_use]
+    pub fn inverted(&self) -> Self {
+        Self::from_index((*self as usize + 3) % 6)
+    }
+}
+
+/// Offset in rows and columns of the axial storage.
+impl From<&HexDirection> for PositionOffset {
+    fn from(value: &HexDirection) -> Self {
+        match value {
+            HexDirection::East => PositionOffset(0, 1),
+            HexDirection::SouthEast => PositionOffset(1, 0),
+            HexDirection::SouthWest => PositionOffset(1, -1),
+            HexDirection::West => PositionOffset(0, -1),
+            HexDirection::NorthWest => PositionOffset(-1, 0),
+            HexDirection::NorthEast => PositionOffset(-1, 1),
+        }
+    }
+}
+
+/// Axial hexagon coordinates, ordered (r, q) like the (y, x) of `Position`. The third cube
+/// coordinate `s` follows from `q + r + s == 0`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Hex(pub isize, pub isize);
+
+impl Hex {
+    pub fn r(&self) -> isize {
+        self.0
+    }
+    pub fn q(&self) -> isize {
+        self.1
+    }
+    pub fn s(&self) -> isize {
+        -self.0 - self.1
+    }
+
+    pub fn moved(&self, direction: &HexDirection) -> Self {
+        let offset: PositionOffset = direction.into();
+        Self(self.0 + offset.0, self.1 + offset.1)
+    }
+
+    pub fn neighbors(&self) -> impl Iterator<Item = (HexDirection, Hex)> + use<> {
+        let hex = *self;
+        HEX_DIRECTIONS
+            .into_iter()
+            .map(move |direction| (direction, hex.moved(&direction)))
+    }
+
+    /// Number of steps between two hexagons.
+    pub fn distance(&self, other: &Hex) -> usize {
+        (self.0.abs_diff(other.0) + self.1.abs_diff(other.1) + self.s().abs_diff(other.s())) / 2
+    }
+
+    /// Turns around the origin by 60 degrees.
+    #[must_use]
+    pub fn rotated(&self, rotational_direction: &RotationalDirection) -> Self {
+        match rotational_direction {
+            RotationalDirection::Clockwise => Self(-self.s(), -self.0),
+            RotationalDirection::Anticlockwise => Self(-self.1, -self.s()),
+        }
+    }
+
+    /// Cell of the axial storage, if it is within the dimensions.
+    pub fn to_position(&self, dimensions: &Dimensions) -> Option<Position> {
+        let (y, x) = (usize::try_from(self.0).ok()?, usize::try_from(self.1).ok()?);
+        (y < dimensions.0 && x < dimensions.1).then_some(Position(y, x))
+    }
+}
+
+impl From<Position> for Hex {
+    fn from(value: Position) -> Self {
+        Hex(value.0 as isize, value.1 as isize)
+    }
+}
+
+impl<T> Grid<T> {
+    /// Neighbors of a hexagon on a board in axial storage that are within the grid.
+    pub fn neighbors_hex(
+        &self,
+        pos: &Position,
+    ) -> impl Iterator<Item = (HexDirection, Position, &T)> {
+        let pos = *pos;
+        HEX_DIRECTIONS.into_iter().filter_map(move |direction| {
+            let next = pos.checked_offset(&self.dimensions, &(&direction).into())?;
+            Some((direction, next, self.get(&next)))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex() {
+        let hex = Hex(2, -1);
+        assert_eq!(hex.s(), -1);
+        assert_eq!(hex.distance(&Hex(0, 0)), 2);
+        assert_eq!(Hex(-3, 1).distance(&Hex(1, 2)), 5);
+        assert!(hex.neighbors().all(|(_, next)| next.distance(&hex) == 1));
+        assert_eq!(
+            HexDirection::NorthEast.rotated(&RotationalDirection::Clockwise),
+            HexDirection::East
+        );
+        assert_eq!(HexDirection::SouthWest.inverted(), HexDirection::NorthEast);
+
+        for direction in HEX_DIRECTIONS {
+            let next = Hex::default().moved(&direction);
+            assert_eq!(
+                next.rotated(&RotationalDirection::Clockwise),
+                Hex::default().moved(&direction.rotated(&RotationalDirection::Clockwise))
+            );
+            assert_eq!(
+                next.rotated(&RotationalDirection::Anticlockwise),
+                Hex::default().moved(&direction.rotated(&RotationalDirection::Anticlockwise))
+            );
+        }
+        assert_eq!(Hex(-1, 0).to_position(&Dimensions(2, 2)), None);
+    }
+
+    #[test]
+    fn grid_neighbors() {
+        let grid = Grid::from_rows(["abc", "def", "ghi"].map(|row| row.bytes()));
+        assert_eq!(
+            grid.neighbors_hex(&Position(1, 1))
+                .map(|(_, _, value)| *value)
+                .collect::<Vec<_>>(),
+            b"fhgdbc"
+        );
+        assert_eq!(grid.neighbors_hex(&Position(0, 0)).count(), 2);
+    }
+}
@@ -1,4 +1,8 @@
+pub mod geometry;
 pub mod grid;
+pub mod grid_n;
+pub mod hash;
+pub mod hex;
 pub mod intset;
 pub mod measure;
 pub mod position;
@@ -1,4 +1,5 @@
-use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+use num::Signed;
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub, SubAssign};
 
 #[derive(Clone, Copy, PartialEq, Debug)]
 pub enum RotationalDirection {
@@ -60,6 +61,10 @@ impl Dimensions {
     pub fn width(&self) -> usize {
         self.1
     }
+    /// Number of cells, the exclusive upper bound of `Position::pack`.
+    pub fn size(&self) -> usize {
+        self.0 * self.1
+    }
 }
 
 impl From<Dimensions> for (usize, usize) {
@@ -177,9 +182,59 @@ impl Position {
         (1..steps + 1).map(move |i| s + offset * i)
     }
 
+    /// Cells of the straight line from `self` to `to`, both included, rasterized with
+    /// Bresenham's algorithm. Consecutive cells are neighbors, possibly diagonal ones.
+    pub fn segment(&self, to: &Position) -> impl Iterator<Item = Position> + use<> {
+        let (end_y, end_x) = (to.0 as isize, to.1 as isize);
+        let (mut y, mut x) = (self.0 as isize, self.1 as isize);
+        let (dy, dx) = (-(end_y - y).abs(), (end_x - x).abs());
+        let (step_y, step_x) = ((end_y - y).signum(), (end_x - x).signum());
+        let mut error = dx + dy;
+        let mut done = false;
+        std::iter::from_fn(move || {
+            if done {
+                return None;
+            }
+            let current = Position(y as usize, x as usize);
+            done = (y, x) == (end_y, end_x);
+            let doubled = 2 * error;
+            if doubled >= dy {
+                error += dy;
+                x += step_x;
+            }
+            if doubled <= dx {
+                error += dx;
+                y += step_y;
+            }
+            Some(current)
+        })
+    }
+
     pub fn manhattan_distance(&self, other: &Position) -> usize {
         self.0.abs_diff(other.0) + self.1.abs_diff(other.1)
     }
+
+    /// Dense row-major index within the dimensions, for compact set and map keys.
+    pub fn pack(&self, dimensions: &Dimensions) -> usize {
+        debug_assert!(self.0 < dimensions.0 && self.1 < dimensions.1);
+        self.0 * dimensions.1 + self.1
+    }
+
+    pub fn unpack(index: usize, dimensions: &Dimensions) -> Self {
+        Self(index / dimensions.1, index % dimensions.1)
+    }
+
+    /// Dense index of a position and direction, below `dimensions.size() * 4`.
+    pub fn pack_with_direction(&self, dimensions: &Dimensions, direction: &Direction) -> usize {
+        (self.pack(dimensions) << 2) | *direction as usize
+    }
+
+    pub fn unpack_with_direction(index: usize, dimensions: &Dimensions) -> (Self, Direction) {
+        (
+            Self::unpack(index >> 2, dimensions),
+            Direction::from_index(index & 3),
+        )
+    }
 }
 struct StepIterator {
     current: isize,
@@ -268,6 +323,161 @@ impl Mul<isize> for PositionOffset {
     }
 }
 
+/// Signed position on an unbounded plane, ordered (y, x) like `Position`. Doubles as a vector,
+/// see `Vec2`, with the usual arithmetic. The coordinate type defaults to `isize`; puzzles with
+/// large numbers can use `Point<i64>` or `Point<i128>`.
+#[derive(Copy, Clone, Debug, Default, Eq, PartialEq, Hash, Ord, PartialOrd)]
+pub struct Point<T = isize>(pub T, pub T);
+
+/// A `Point` used as a displacement between points.
+pub type Vec2<T = isize> = Point<T>;
+
+impl<T: Copy> Point<T> {
+    pub fn y(&self) -> T {
+        self.0
+    }
+    pub fn x(&self) -> T {
+        self.1
+    }
+}
+
+impl<T: Copy + Signed> Point<T> {
+    pub fn dot(&self, other: &Self) -> T {
+        self.0 * other.0 + self.1 * other.1
+    }
+
+    /// Z component of the cross product in (x, y) order, `x * other.y - y * other.x`. It is
+    /// positive when `other` is clockwise of `self` on screen, where y grows downwards.
+    pub fn cross(&self, other: &Self) -> T {
+        self.1 * other.0 - self.0 * other.1
+    }
+
+    pub fn manhattan_length(&self) -> T {
+        self.0.abs() + self.1.abs()
+    }
+
+    pub fn manhattan_distance(&self, other: &Self) -> T {
+        (*other - *self).manhattan_length()
+    }
+
+    #[must_use]
+    pub fn signum(&self) -> Self {
+        Self(self.0.signum(), self.1.signum())
+    }
+
+    /// Turns around the origin by 90 degrees, as seen on screen.
+    #[must_use]
+    pub fn rotated(&self, rotational_direction: &RotationalDirection) -> Self {
+        match rotational_direction {
+            RotationalDirection::Clockwise => Self(self.1, -self.0),
+            RotationalDirection::Anticlockwise => Self(-self.1, self.0),
+        }
+    }
+
+    /// Position within the dimensions, `None` for points outside of them.
+    pub fn to_position(&self, dimensions: &Dimensions) -> Option<Position>
+    where
+        T: TryInto<usize>,
+    {
+        let (y, x) = (self.0.try_into().ok()?, self.1.try_into().ok()?);
+        (y < dimensions.0 && x < dimensions.1).then_some(Position(y, x))
+    }
+
+    /// Point of a position within the dimensions, `None` if it is outside of them or does not fit
+    /// into `T`.
+    pub fn from_position(pos: &Position, dimensions: &Dimensions) -> Option<Self>
+    where
+        T: TryFrom<usize>,
+    {
+        if pos.0 >= dimensions.0 || pos.1 >= dimensions.1 {
+            return None;
+        }
+        Some(Self(pos.0.try_into().ok()?, pos.1.try_into().ok()?))
+    }
+}
+
+impl Point {
+    pub fn moved(&self, direction: &Direction) -> Self {
+        self.offset(&direction.into())
+    }
+
+    pub fn offset(&self, offset: &PositionOffset) -> Self {
+        Self(self.0 + offset.0, self.1 + offset.1)
+    }
+}
+
+impl From<Position> for Point {
+    fn from(value: Position) -> Self {
+        Point(value.0 as isize, value.1 as isize)
+    }
+}
+
+impl From<PositionOffset> for Point {
+    fn from(value: PositionOffset) -> Self {
+        Point(value.0, value.1)
+    }
+}
+
+impl From<Point> for PositionOffset {
+    fn from(value: Point) -> Self {
+        PositionOffset(value.0, value.1)
+    }
+}
+
+impl<T> From<(T, T)> for Point<T> {
+    fn from(value: (T, T)) -> Self {
+        Point(value.0, value.1)
+    }
+}
+
+impl<T: Add<Output = T>> Add for Point<T> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self::Output {
+        Self(self.0 + rhs.0, self.1 + rhs.1)
+    }
+}
+impl<T: AddAssign> AddAssign for Point<T> {
+    fn add_assign(&mut self, rhs: Self) {
+        self.0 += rhs.0;
+        self.1 += rhs.1;
+    }
+}
+impl<T: Sub<Output = T>> Sub for Point<T> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self::Output {
+        Self(self.0 - rhs.0, self.1 - rhs.1)
+    }
+}
+impl<T: SubAssign> SubAssign for Point<T> {
+    fn sub_assign(&mut self, rhs: Self) {
+        self.0 -= rhs.0;
+        self.1 -= rhs.1;
+    }
+}
+impl<T: Neg<Output = T>> Neg for Point<T> {
+    type Output = Self;
+
+    fn neg(self) -> Self::Output {
+        Self(-self.0, -self.1)
+    }
+}
+impl<T: Copy + Mul<Output = T>> Mul<T> for Point<T> {
+    type Output = Self;
+
+    fn mul(self, rhs: T) -> Self::Output {
+        Self(self.0 * rhs, self.1 * rhs)
+    }
+}
+impl<T: Copy + Div<Output = T>> Div<T> for Point<T> {
+    type Output = Self;
+
+    fn div(self, rhs: T) -> Self::Output {
+        Self(self.0 / rhs, self.1 / rhs)
+    }
+}
+
 #[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
 pub enum Direction {
     Up = 0,
@@ -282,7 +492,30 @@ pub const DIRECTIONS: [Direction; 4] = [
     Direction::Right,
 ];
 
+/// Offsets to all eight neighbors, clockwise starting from up.
+pub const NEIGHBOR_OFFSETS: [PositionOffset; 8] = [
+    PositionOffset(-1, 0),
+    PositionOffset(-1, 1),
+    PositionOffset(0, 1),
+    PositionOffset(1, 1),
+    PositionOffset(1, 0),
+    PositionOffset(1, -1),
+    PositionOffset(0, -1),
+    PositionOffset(-1, -1),
+];
+
 impl Direction {
+    /// Direction that is `index` quarter turns clockwise from up, which is how
+    /// `Position::pack_with_direction` stores it.
+    pub fn from_index(index: usize) -> Self {
+        [
+            Direction::Up,
+            Direction::Right,
+            Direction::Down,
+            Direction::Left,
+        ][index]
+    }
+
     #[must_use]
     pub fn rotated(self, rotational_direction: &RotationalDirection) -> Self {
         match (self, rotational_direction) {
@@ -318,3 +551,200 @@ impl From<&Direction> for PositionOffset {
         }
     }
 }
+
+/// Direction to one of the eight surrounding cells. The discriminants count clockwise from up in
+/// 45 degree steps, matching `NEIGHBOR_OFFSETS`.
+#[derive(Clone, Copy, PartialEq, Debug, Eq, Hash)]
+pub enum Direction8 {
+    Up = 0,
+    UpRight = 1,
+    Right = 2,
+    DownRight = 3,
+    Down = 4,
+    DownLeft = 5,
+    Left = 6,
+    UpLeft = 7,
+}
+pub const DIRECTIONS8: [Direction8; 8] = [
+    Direction8::Up,
+    Direction8::UpRight,
+    Direction8::Right,
+    Direction8::DownRight,
+    Direction8::Down,
+    Direction8::DownLeft,
+    Direction8::Left,
+    Direction8::UpLeft,
+];
+
+impl Direction8 {
+    /// Direction that is `index` times 45 degrees clockwise from up, `index` must be below 8.
+    pub fn from_index(index: usize) -> Self {
+        DIRECTIONS8[index]
+    }
+
+    pub fn is_diagonal(&self) -> bool {
+        *self as usize % 2 == 1
+    }
+
+    /// Turns by 45 degrees.
+    #[must_use]
+    pub fn rotated(self, rotational_direction: &RotationalDirection) -> Self {
+        match rotational_direction {
+            RotationalDirection::Clockwise => self.rotated_steps(1),
+            RotationalDirection::Anticlockwise => self.rotated_steps(-1),
+        }
+    }
+
+    /// Turns clockwise by `steps` times 45 degrees, anticlockwise for negative steps.
+    #[must_use]
+    pub fn rotated_steps(self, steps: isize) -> Self {
+        Self::from_index((self as isize + steps).rem_euclid(8) as usize)
+    }
+
+    #[must_use]
+    pub fn inverted(&self) -> Self {
+        self.rotated_steps(4)
+    }
+}
+
+impl From<Direction> for Direction8 {
+    fn from(value: Direction) -> Self {
+        match value {
+            Direction::Up => Direction8::Up,
+            Direction::Right => Direction8::Right,
+            Direction::Down => Direction8::Down,
+            Direction::Left => Direction8::Left,
+        }
+    }
+}
+
+impl TryFrom<Direction8> for Direction {
+    type Error = Direction8;
+
+    /// Fails for diagonals, which have no four-way equivalent.
+    fn try_from(value: Direction8) -> Result<Self, Self::Error> {
+        match value {
+            Direction8::Up => Ok(Direction::Up),
+            Direction8::Right => Ok(Direction::Right),
+            Direction8::Down => Ok(Direction::Down),
+            Direction8::Left => Ok(Direction::Left),
+            diagonal => Err(diagonal),
+        }
+    }
+}
+
+impl From<&Direction8> for PositionOffset {
+    fn from(value: &Direction8) -> Self {
+        NEIGHBOR_OFFSETS[*value as usize]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pack() {
+        let dimensions = Dimensions(3, 5);
+        let packed = (0..3)
+            .flat_map(|y| (0..5).map(move |x| Position(y, x)))
+            .map(|pos| pos.pack(&dimensions))
+            .collect::<Vec<_>>();
+        assert_eq!(packed, (0..dimensions.size()).collect::<Vec<_>>());
+        assert_eq!(Position::unpack(7, &dimensions), Position(1, 2));
+
+        let index = Position(2, 4).pack_with_direction(&dimensions, &Direction::Left);
+        assert!(index < dimensions.size() * 4);
+        assert_eq!(
+            Position::unpack_with_direction(index, &dimensions),
+            (Position(2, 4), Direction::Left)
+        );
+    }
+
+    #[test]
+    fn segment() {
+        assert_eq!(
+            Position(0, 0).segment(&Position(2, 5)).collect::<Vec<_>>(),
+            [
+                Position(0, 0),
+                Position(0, 1),
+                Position(1, 2),
+                Position(1, 3),
+                Position(2, 4),
+                Position(2, 5)
+            ]
+        );
+        assert_eq!(
+            Position(3, 1).segment(&Position(0, 1)).collect::<Vec<_>>(),
+            [
+                Position(3, 1),
+                Position(2, 1),
+                Position(1, 1),
+                Position(0, 1)
+            ]
+        );
+        assert_eq!(
+            Position(4, 4).segment(&Position(4, 4)).collect::<Vec<_>>(),
+            [Position(4, 4)]
+        );
+    }
+
+    #[test]
+    fn point() {
+        let (a, b): (Vec2, Vec2) = (Point(1, 2), Point(-3, 4));
+        assert_eq!(a + b, Point(-2, 6));
+        assert_eq!(a - b, Point(4, -2));
+        assert_eq!(-a * 3, Point(-3, -6));
+        assert_eq!(b / 2, Point(-1, 2));
+        assert_eq!(a.dot(&b), 5);
+        assert_eq!(a.cross(&b), -10);
+        assert_eq!(b.manhattan_distance(&a), 6);
+        assert_eq!(Point(-4, 0).signum(), Point(-1, 0));
+
+        let up = Point::from(PositionOffset::up());
+        assert_eq!(
+            up.rotated(&RotationalDirection::Clockwise),
+            Point::from(PositionOffset::right())
+        );
+        assert_eq!(up.rotated(&RotationalDirection::Anticlockwise).x(), -1);
+
+        let dimensions = Dimensions(3, 4);
+        assert_eq!(Point(2, 3).to_position(&dimensions), Some(Position(2, 3)));
+        assert_eq!(Point(-1, 0).to_position(&dimensions), None);
+        assert_eq!(Point(0, 4).to_position(&dimensions), None);
+        assert_eq!(
+            Point::<i8>::from_position(&Position(1, 2), &dimensions),
+            Some(Point(1, 2))
+        );
+        assert_eq!(
+            Point::<i8>::from_position(&Position(300, 0), &Dimensions(400, 1)),
+            None
+        );
+        assert_eq!(
+            Point::<i64>::from_position(&Position(3, 0), &dimensions),
+            None
+        );
+    }
+
+    #[test]
+    fn direction8() {
+        let offsets = DIRECTIONS8.map(|direction| PositionOffset::from(&direction));
+        assert_eq!(offsets, NEIGHBOR_OFFSETS);
+        assert_eq!(
+            Direction8::UpLeft.rotated(&RotationalDirection::Clockwise),
+            Direction8::Up
+        );
+        assert_eq!(Direction8::Up.rotated_steps(-3), Direction8::DownLeft);
+        assert_eq!(Direction8::DownRight.inverted(), Direction8::UpLeft);
+        assert!(Direction8::DownLeft.is_diagonal());
+        assert_eq!(Direction8::from(Direction::Left), Direction8::Left);
+        assert_eq!(
+            Direction::try_from(Direction8::UpRight),
+            Err(Direction8::UpRight)
+        );
+        assert_eq!(
+            PositionOffset::from(&Direction8::Right),
+            (&Direction::Right).into()
+        );
+    }
+}
@@ -1,4 +1,4 @@
-use rustc_hash::FxHashSet;
+use crate::util::hash::FxHashSet;
 use std::collections::BinaryHeap;
 use std::hash::Hash;
 use std::ops::ControlFlow;
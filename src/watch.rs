@@ -0,0 +1,148 @@
+use std::collections::VecDeque;
+use std::fmt::{Display, Formatter};
+use std::path::Path;
+use std::process::Output;
+use std::time::{Duration, Instant, SystemTime};
+
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+const HISTORY: usize = 8;
+
+/// Result of a single solve, parsed from the output of the solver process.
+#[derive(Debug, Default, Clone, PartialEq)]
+struct Run {
+    part1: Option<String>,
+    part2: Option<String>,
+    solve_time: Option<String>,
+    error: Option<String>,
+}
+
+impl Run {
+    fn from_output(day: usize, output: &Output) -> Self {
+        let mut run = Self::from_stdout(day, &String::from_utf8_lossy(&output.stdout));
+        if !output.status.success() {
+            run.error = Some(
+                String::from_utf8_lossy(&output.stderr)
+                    .lines()
+                    .map(str::trim)
+                    .rfind(|line| !line.is_empty() && !line.starts_with("note:"))
+                    .unwrap_or("failed")
+                    .to_string(),
+            );
+        }
+        run
+    }
+
+    fn from_stdout(day: usize, stdout: &str) -> Self {
+        let prefix = format!("day{}/", day);
+        let mut run = Self::default();
+        stdout
+            .lines()
+            .filter_map(|line| line.strip_prefix(&prefix)?.split_once(": "))
+            .for_each(|(key, value)| {
+                let value = Some(value.to_string());
+                match key {
+                    "part1" => run.part1 = value,
+                    "part2" => run.part2 = value,
+                    "solve_time" => run.solve_time = value,
+                    _ => {}
+                }
+            });
+        run
+    }
+}
+
+struct HistoryEntry {
+    elapsed: Duration,
+    run: Run,
+}
+
+impl Display for HistoryEntry {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{:>8.1?}  ", self.elapsed)?;
+        if let Some(error) = &self.run.error {
+            return write!(f, "error: {}", error);
+        }
+        write!(
+            f,
+            "part1: {}  part2: {}  time: {}",
+            self.run.part1.as_deref().unwrap_or("-"),
+            self.run.part2.as_deref().unwrap_or("-"),
+            self.run.solve_time.as_deref().unwrap_or("-"),
+        )
+    }
+}
+
+fn modified(paths: &[String]) -> Vec<Option<SystemTime>> {
+    paths
+        .iter()
+        .map(|path| Path::new(path).metadata().and_then(|m| m.modified()).ok())
+        .collect()
+}
+
+/// Polls the given paths and calls `solve` whenever one of them changes, keeping a short history
+/// of the answers and timings on screen. Never returns, stop it with Ctrl-C.
+pub fn watch(day: usize, paths: &[String], mut solve: impl FnMut() -> std::io::Result<Output>) {
+    let start = Instant::now();
+    let mut history = VecDeque::with_capacity(HISTORY);
+    let mut last_modified = None;
+
+    loop {
+        let current_modified = modified(paths);
+        if last_modified.as_ref() == Some(&current_modified) {
+            std::thread::sleep(POLL_INTERVAL);
+            continue;
+        }
+        last_modified = Some(current_modified);
+
+        let run = match solve() {
+            Ok(output) => Run::from_output(day, &output),
+            Err(e) => Run {
+                error: Some(e.to_string()),
+                ..Default::default()
+            },
+        };
+        if history.len() == HISTORY {
+            history.pop_back();
+        }
+        history.push_front(HistoryEntry {
+            elapsed: start.elapsed(),
+            run,
+        });
+
+        // Clear the screen and move the cursor to the top left
+        print!("\x1b[2J\x1b[H");
+        println!("Watching day{:0>2}: {}", day, paths.join(", "));
+        history.iter().for_each(|entry| println!("{}", entry));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parse_stdout() {
+        assert_eq!(
+            Run::from_stdout(
+                5,
+                "day5/part1: 143\nday5/part2: 123\nday5/solve_time: 1.2ms (prepare: 1ms)\n"
+            ),
+            Run {
+                part1: Some("143".to_string()),
+                part2: Some("123".to_string()),
+                solve_time: Some("1.2ms (prepare: 1ms)".to_string()),
+                error: None,
+            }
+        );
+    }
+    #[test]
+    fn parse_stdout_other_day() {
+        assert_eq!(
+            Run::from_stdout(5, "day15/part1: 1\nday5/part1: 3\n"),
+            Run {
+                part1: Some("3".to_string()),
+                ..Default::default()
+            }
+        );
+    }
+}